@@ -0,0 +1,100 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use egui::{Context, Pos2};
+use egui_skia::EguiSkia;
+use skia_safe::Surface;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The demo windows together with the color test, which produces a lot of small meshes.
+fn heavy_scene() -> impl FnMut(&Context) {
+    let mut demo = egui_demo_lib::DemoWindows::default();
+    let mut color_test = egui_demo_lib::ColorTest::default();
+    move |ctx| {
+        demo.ui(ctx);
+        egui::Window::new("Color test").show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| color_test.ui(ui));
+        });
+    }
+}
+
+/// Paints the heavy scene every iteration, with the vertex cache enabled if
+/// `max_unused_frames` is set.
+fn bench_paint(c: &mut Criterion, name: &str, max_unused_frames: Option<u64>) {
+    let mut ui = heavy_scene();
+    let mut surface =
+        Surface::new_raster_n32_premul((1280, 1024)).expect("Failed to create surface");
+    let mut egui_skia = EguiSkia::new();
+    egui_skia.painter.set_vertex_cache(max_unused_frames);
+
+    let input = egui::RawInput {
+        screen_rect: Some(
+            [
+                Pos2::default(),
+                Pos2::new(surface.width() as f32, surface.height() as f32),
+            ]
+            .into(),
+        ),
+        pixels_per_point: Some(1.0),
+        ..Default::default()
+    };
+
+    // Let egui lay out the windows and upload the textures before measuring
+    for _ in 0..2 {
+        egui_skia.run(input.clone(), &mut ui);
+        egui_skia.paint(surface.canvas());
+    }
+
+    egui_skia.run(input.clone(), &mut ui);
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    egui_skia.paint(surface.canvas());
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    println!(
+        "{}: allocations per paint: {} ({} meshes, {} draw calls, {} cached)",
+        name,
+        allocations,
+        egui_skia.painter.stats().meshes,
+        egui_skia.painter.stats().draw_calls,
+        egui_skia.painter.stats().cached_vertices,
+    );
+
+    c.bench_function(name, |b| {
+        b.iter_custom(|iters| {
+            let mut elapsed = Duration::ZERO;
+            for _ in 0..iters {
+                egui_skia.run(input.clone(), &mut ui);
+                let start = Instant::now();
+                egui_skia.paint(surface.canvas());
+                elapsed += start.elapsed();
+            }
+            elapsed
+        })
+    });
+}
+
+fn paint(c: &mut Criterion) {
+    bench_paint(c, "paint heavy scene", None);
+    bench_paint(c, "paint heavy scene with vertex cache", Some(60));
+}
+
+criterion_group!(benches, paint);
+criterion_main!(benches);
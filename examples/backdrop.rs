@@ -0,0 +1,52 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui::{Color32, Id, LayerId, Order};
+use egui_skia::{rasterize, ImageFilterEffect, RasterizeOptions};
+use skia_safe::EncodedImageFormat;
+
+const TITLE: &str = "Frosted glass";
+
+/// Blurs the demo windows behind a translucent window.
+pub fn main() {
+    let mut demo = egui_demo_lib::DemoWindows::default();
+    let mut window_rect: Option<egui::Rect> = None;
+
+    let mut surface = rasterize(
+        (1024, 756),
+        |ctx| {
+            demo.ui(ctx);
+
+            // The backdrop has to be painted before the window's background, which is only known
+            // after showing the window, so use the rect of the last frame.
+            if let Some(rect) = window_rect {
+                ctx.layer_painter(LayerId::new(Order::Middle, Id::new(TITLE)))
+                    .add(ImageFilterEffect::backdrop_blur(rect, 6.0, 8.0));
+            }
+
+            let frame = egui::Frame::window(&ctx.style())
+                .fill(Color32::from_rgba_unmultiplied(40, 40, 40, 160));
+            let response = egui::Window::new(TITLE)
+                .frame(frame)
+                .default_pos((300.0, 200.0))
+                .show(ctx, |ui| {
+                    ui.label("The windows behind this one are blurred.");
+                    ui.allocate_space(egui::vec2(300.0, 200.0));
+                });
+            window_rect = response.map(|response| response.response.rect);
+        },
+        Some(RasterizeOptions::default()),
+    );
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
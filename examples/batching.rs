@@ -0,0 +1,49 @@
+use std::time::Instant;
+
+use egui::Pos2;
+use egui_skia::EguiSkia;
+use skia_safe::Surface;
+
+const FRAMES: usize = 100;
+
+pub fn main() {
+    let mut demo = egui_demo_lib::DemoWindows::default();
+    let mut surface =
+        Surface::new_raster_n32_premul((1024, 756)).expect("Failed to create surface");
+    let mut egui_skia = EguiSkia::new();
+    if std::env::args().any(|arg| arg == "--vertex-cache") {
+        egui_skia.painter.set_vertex_cache(Some(60));
+    }
+
+    let input = egui::RawInput {
+        screen_rect: Some(
+            [
+                Pos2::default(),
+                Pos2::new(surface.width() as f32, surface.height() as f32),
+            ]
+            .into(),
+        ),
+        pixels_per_point: Some(1.0),
+        ..Default::default()
+    };
+
+    // Let egui lay out the windows before measuring
+    for _ in 0..2 {
+        egui_skia.run(input.clone(), |ctx| demo.ui(ctx));
+        egui_skia.paint(surface.canvas());
+    }
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        egui_skia.run(input.clone(), |ctx| demo.ui(ctx));
+        egui_skia.paint(surface.canvas());
+    }
+    let elapsed = start.elapsed();
+
+    let stats = egui_skia.painter.stats();
+    println!("meshes:     {}", stats.meshes);
+    println!("draw calls: {}", stats.draw_calls);
+    println!("callbacks:  {}", stats.callbacks);
+    println!("cached:     {}", stats.cached_vertices);
+    println!("frame time: {:?}", elapsed / FRAMES as u32);
+}
@@ -1,9 +1,22 @@
-use egui_skia::rasterize;
+use egui_skia::{rasterize, CpuFix, DebugOverlay, RasterizeOptions};
 use skia_safe::EncodedImageFormat;
 use std::fs::File;
 use std::io::Write;
 
 pub fn main() {
+    // Pass --gpu-path to render without the raster workaround, to compare both paths
+    let cpu_fix = if std::env::args().any(|arg| arg == "--gpu-path") {
+        CpuFix::Never
+    } else {
+        CpuFix::Auto
+    };
+    // Pass --debug-overlay to show the meshes, clip rects and textures the painter submitted
+    let debug_overlay = if std::env::args().any(|arg| arg == "--debug-overlay") {
+        DebugOverlay::all()
+    } else {
+        DebugOverlay::default()
+    };
+
     let mut demo = egui_demo_lib::ColorTest::default();
 
     let mut surface = rasterize(
@@ -13,7 +26,11 @@ pub fn main() {
                 demo.ui(ui);
             });
         },
-        None,
+        Some(RasterizeOptions {
+            cpu_fix,
+            debug_overlay,
+            ..Default::default()
+        }),
     );
 
     let data = surface
@@ -109,8 +109,6 @@ fn main() {
     use skia_safe::{Paint, Point};
 
     use egui_skia::EguiSkiaPaintCallback;
-    #[cfg(not(feature = "cpu_fix"))]
-    eprintln!("Warning! Feature cpu_fix should be enabled when using raster surfaces. See https://github.com/lucasmerlin/egui_skia/issues/1");
 
     let mut demos = egui_demo_lib::DemoWindows::default();
     run_software(move |ctx| {
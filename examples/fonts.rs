@@ -0,0 +1,42 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui::{Color32, FontFamily};
+use egui_skia::{rasterize, ColorText, FontDefinitionsBuilder, RasterizeOptions};
+use skia_safe::{EncodedImageFormat, FontMgr};
+
+/// Uses system fonts with fallbacks for egui's text and draws color emoji with skia.
+pub fn main() {
+    let mut fonts = FontDefinitionsBuilder::new();
+    for name in ["Noto Sans", "DejaVu Sans", "Arial"] {
+        fonts.push_family(FontFamily::Proportional, name);
+    }
+    fonts.push_fallback_for(FontFamily::Proportional, '漢');
+    let fonts = fonts.build();
+
+    let emoji = ColorText::new(&FontMgr::new(), "Color emoji: 🦀🎨🚀", 24.0, Color32::WHITE);
+
+    let mut surface = rasterize(
+        (512, 256),
+        |ctx| {
+            ctx.set_fonts(fonts.clone());
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.label("System fonts with fallback: 漢字");
+                emoji.show(ui);
+            });
+        },
+        Some(RasterizeOptions::default()),
+    );
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
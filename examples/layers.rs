@@ -0,0 +1,56 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui::{Order, Pos2};
+use egui_skia::{EguiSkia, LayerStyle};
+use skia_safe::{Color, EncodedImageFormat, Paint, Point, Surface};
+
+/// Draws host content between egui's background panels and its windows, and fades the
+/// windows.
+pub fn main() {
+    let mut surface =
+        Surface::new_raster_n32_premul((1024, 756)).expect("Failed to create surface");
+    let mut egui_skia = EguiSkia::new();
+    egui_skia.set_layer_painting(true);
+    let mut demo = egui_demo_lib::DemoWindows::default();
+
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(1024.0, 756.0)].into()),
+        ..Default::default()
+    };
+    for _ in 0..2 {
+        egui_skia.run(input.clone(), |ctx| demo.ui(ctx));
+    }
+
+    let mut world_drawn = false;
+    egui_skia.paint_layers(surface.canvas(), |canvas, layer_id| {
+        if layer_id.order == Order::Background {
+            return LayerStyle::default();
+        }
+
+        // The "world", drawn above the background panels and below everything else
+        if !world_drawn {
+            let mut paint = Paint::default();
+            paint.set_color(Color::from_rgb(40, 160, 90));
+            canvas.draw_circle(Point::new(512.0, 378.0), 300.0, &paint);
+            world_drawn = true;
+        }
+
+        LayerStyle {
+            opacity: 0.85,
+            ..Default::default()
+        }
+    });
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
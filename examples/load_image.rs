@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui::Pos2;
+use egui_skia::EguiSkia;
+use skia_safe::{Color, EncodedImageFormat, Paint, Point, Surface};
+
+/// Creates a png to load, any other format supported by skia works the same.
+fn encoded_image() -> Vec<u8> {
+    let mut surface = Surface::new_raster_n32_premul((256, 256)).unwrap();
+    let canvas = surface.canvas();
+    canvas.clear(Color::WHITE);
+    canvas.draw_circle(
+        Point::new(128.0, 128.0),
+        100.0,
+        Paint::default().set_color(Color::from_rgb(200, 60, 40)),
+    );
+
+    surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .unwrap()
+        .as_bytes()
+        .to_vec()
+}
+
+/// Decodes an image with skia and shows it in an egui window.
+pub fn main() {
+    let mut surface = Surface::new_raster_n32_premul((512, 512)).expect("Failed to create surface");
+    let mut egui_skia = EguiSkia::new();
+
+    let (texture_id, size) = egui_skia
+        .painter
+        .load_image(&encoded_image(), Default::default())
+        .expect("Failed to decode image");
+
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(512.0, 512.0)].into()),
+        ..Default::default()
+    };
+    for _ in 0..2 {
+        egui_skia.run(input.clone(), |ctx| {
+            egui::Window::new("Image").show(ctx, |ui| {
+                ui.image(texture_id, size);
+            });
+        });
+    }
+    egui_skia.paint(surface.canvas());
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
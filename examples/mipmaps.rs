@@ -0,0 +1,55 @@
+use egui::{Color32, ColorImage, TextureOptions, Vec2};
+use egui_skia::rasterize;
+use skia_safe::EncodedImageFormat;
+use std::fs::File;
+use std::io::Write;
+
+/// Renders a fine checkerboard downscaled. With mipmaps the result is a smooth gray,
+/// without it shows aliasing patterns.
+pub fn main() {
+    let size = 512;
+    let checkerboard = ColorImage {
+        size: [size, size],
+        pixels: (0..size * size)
+            .map(|i| {
+                if (i % size + i / size) % 2 == 0 {
+                    Color32::BLACK
+                } else {
+                    Color32::WHITE
+                }
+            })
+            .collect(),
+    };
+
+    let mut texture = None;
+
+    let mut surface = rasterize(
+        (400, 400),
+        |ctx| {
+            let texture = texture.get_or_insert_with(|| {
+                ctx.load_texture("checkerboard", checkerboard.clone(), TextureOptions::LINEAR)
+            });
+
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for scale in [0.5, 0.33, 0.2, 0.1] {
+                        ui.image(texture.id(), Vec2::splat(size as f32 * scale));
+                    }
+                });
+            });
+        },
+        None,
+    );
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
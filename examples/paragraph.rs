@@ -0,0 +1,65 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui_skia::{rasterize, system_font_collection, ParagraphLabel, RasterizeOptions};
+use skia_safe::textlayout::{
+    ParagraphBuilder, ParagraphStyle, TextDecoration, TextDirection, TextStyle,
+};
+use skia_safe::{Color, EncodedImageFormat};
+
+fn paragraph(text: &str, direction: TextDirection) -> ParagraphLabel {
+    let mut text_style = TextStyle::new();
+    text_style.set_font_size(20.0);
+    text_style.set_color(Color::from_rgb(220, 220, 220));
+
+    let mut paragraph_style = ParagraphStyle::new();
+    paragraph_style.set_text_direction(direction);
+    paragraph_style.set_text_style(&text_style);
+
+    let mut builder = ParagraphBuilder::new(&paragraph_style, system_font_collection());
+    builder.add_text(text);
+
+    let mut underlined = text_style.clone();
+    underlined.set_decoration_type(TextDecoration::UNDERLINE);
+    builder.push_style(&underlined);
+    builder.add_text(" (underlined)");
+    builder.pop();
+
+    ParagraphLabel::new(builder.build())
+}
+
+/// Shows text in scripts egui can't shape, laid out by skia.
+pub fn main() {
+    let mut labels = [
+        paragraph("مرحبا بالعالم", TextDirection::RTL),
+        paragraph("नमस्ते दुनिया", TextDirection::LTR),
+        paragraph(
+            "Hello world, with a fallback font for ✓",
+            TextDirection::LTR,
+        ),
+    ];
+
+    let mut surface = rasterize(
+        (512, 256),
+        |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                for label in &mut labels {
+                    label.show(ui);
+                }
+            });
+        },
+        Some(RasterizeOptions::default()),
+    );
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
@@ -32,7 +32,8 @@ pub fn main() {
         },
         Some(RasterizeOptions {
             pixels_per_point: 1.0,
-            frames_before_screenshot: 2
+            frames_before_screenshot: 2,
+            ..Default::default()
         }),
     );
 
@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui_skia::{rasterize, RasterizeOptions, ShaderFill};
+use skia_safe::EncodedImageFormat;
+
+const GRADIENT: &str = r#"
+uniform float time;
+uniform float2 size;
+uniform float2 pointer;
+uniform float3 tint;
+
+half4 main(float2 coord) {
+    float2 uv = coord / size;
+    float wave = 0.5 + 0.5 * sin(uv.x * 6.0 + time * 2.0);
+    float glow = pointer.x < 0.0 ? 0.0 : 0.3 * (1.0 - smoothstep(0.0, 80.0, distance(coord, pointer)));
+    return half4(half3(mix(tint, float3(uv, 1.0), wave) + glow), 1.0);
+}
+"#;
+
+/// Draws an animated gradient header with an SkSL shader.
+pub fn main() {
+    let mut header = ShaderFill::new(GRADIENT).expect("Failed to compile shader");
+    header.set_uniform("tint", &[0.2, 0.3, 0.8]);
+
+    let mut surface = rasterize(
+        (512, 256),
+        |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let response = header.show(ui, egui::vec2(ui.available_width(), 64.0));
+                ui.painter().text(
+                    response.rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "Runtime effect header",
+                    egui::FontId::proportional(24.0),
+                    egui::Color32::WHITE,
+                );
+            });
+        },
+        Some(RasterizeOptions::default()),
+    );
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
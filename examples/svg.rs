@@ -0,0 +1,44 @@
+use std::fs::File;
+use std::io::Write;
+
+use egui_skia::{rasterize, RasterizeOptions, SvgImage};
+use skia_safe::EncodedImageFormat;
+
+const ICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <circle cx="12" cy="12" r="10" fill="#3a7bd5"/>
+  <path d="M7 12l3 3 7-7" stroke="white" stroke-width="2" fill="none"/>
+</svg>"##;
+
+/// Draws an svg icon at several sizes, it stays sharp at all of them.
+pub fn main() {
+    let icon = SvgImage::from_str(ICON, egui::vec2(24.0, 24.0)).expect("Failed to parse svg");
+
+    let mut surface = rasterize(
+        (512, 512),
+        |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for scale in [1.0, 2.0, 4.0, 8.0] {
+                        icon.show_scaled(ui, scale);
+                    }
+                });
+            });
+        },
+        Some(RasterizeOptions {
+            pixels_per_point: 2.0,
+            ..Default::default()
+        }),
+    );
+
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
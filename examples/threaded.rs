@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io::Write;
+use std::sync::mpsc;
+use std::thread;
+
+use egui::Pos2;
+use egui_skia::{EguiSkia, FrameOutput, Painter};
+use skia_safe::{Color, EncodedImageFormat, Surface};
+
+const FRAMES: usize = 100;
+
+/// Runs the ui on the main thread and paints the frames on a render thread.
+pub fn main() {
+    let size = (1024, 756);
+    let mut egui_skia = EguiSkia::new();
+    let (sender, receiver) = mpsc::sync_channel::<FrameOutput>(1);
+
+    let painter = Painter::new_with_texture_store(
+        egui_skia.painter.options().clone(),
+        egui_skia.painter.texture_store().clone(),
+    );
+    let render_thread = thread::spawn(move || {
+        let mut painter = painter;
+        let mut surface = Surface::new_raster_n32_premul(size).expect("Failed to create surface");
+
+        while let Ok(mut frame) = receiver.recv() {
+            // Skip to the newest frame if the ui thread is ahead.
+            while let Ok(newer) = receiver.try_recv() {
+                frame.append(newer);
+            }
+            surface.canvas().clear(Color::TRANSPARENT);
+            painter.paint_frame(surface.canvas(), frame);
+        }
+
+        surface
+    });
+
+    let mut demo = egui_demo_lib::DemoWindows::default();
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(size.0 as f32, size.1 as f32)].into()),
+        ..Default::default()
+    };
+    for _ in 0..FRAMES {
+        let (_, _, frame) = egui_skia.run_detached(input.clone(), |ctx| demo.ui(ctx));
+        sender.send(frame).unwrap();
+    }
+    drop(sender);
+
+    let mut surface = render_thread.join().unwrap();
+    let data = surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image");
+
+    File::create("output.png")
+        .unwrap()
+        .write_all(&data)
+        .unwrap();
+
+    println!("wrote output.png");
+}
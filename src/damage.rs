@@ -0,0 +1,415 @@
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use egui::epaint::ahash::{AHashMap, AHashSet, AHasher};
+use egui::epaint::Primitive;
+use egui::{ClippedPrimitive, TextureId, TexturesDelta};
+use skia_safe::{IRect, ISize};
+
+use crate::shadows::NativeShadow;
+use crate::vertex_cache::hash_vertices;
+
+/// If more damage rects than this remain after merging, they are joined into a single one.
+const MAX_DAMAGE_RECTS: usize = 8;
+
+struct PrimitiveSignature {
+    hash: u64,
+    /// Bounds of the primitive in points, clipped to its clip rect.
+    bounds: egui::Rect,
+    /// Primitives whose content cannot be compared between frames (e.g. paint callbacks),
+    /// these are always considered damaged.
+    volatile: bool,
+}
+
+struct Frame {
+    size: ISize,
+    pixels_per_point: f32,
+    primitives: Vec<PrimitiveSignature>,
+}
+
+/// Compares the clipped primitives of consecutive frames to find the regions of the screen
+/// that have to be repainted.
+pub(crate) struct DamageTracker {
+    previous: Option<Frame>,
+}
+
+impl DamageTracker {
+    pub fn new() -> Self {
+        Self { previous: None }
+    }
+
+    /// Forgets the previous frame, so the next call to [`Self::damage`] damages the whole canvas.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Returns the damaged regions in pixels, compared to the frame passed in the previous call.
+    pub fn damage(
+        &mut self,
+        size: ISize,
+        pixels_per_point: f32,
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+    ) -> Vec<IRect> {
+        let full = IRect::from_size(size);
+
+        let changed_textures = changed_textures(textures_delta);
+        let current = Frame {
+            size,
+            pixels_per_point,
+            primitives: primitives
+                .iter()
+                .map(|primitive| signature(primitive, &changed_textures))
+                .collect(),
+        };
+
+        let previous = match self.previous.replace(current) {
+            Some(previous)
+                if previous.size == size && previous.pixels_per_point == pixels_per_point =>
+            {
+                previous
+            }
+            _ => return vec![full],
+        };
+        let current = self.previous.as_ref().unwrap();
+
+        let mut damaged = Vec::new();
+
+        let mut previous_indices: AHashMap<u64, VecDeque<usize>> = AHashMap::new();
+        for (index, primitive) in previous.primitives.iter().enumerate() {
+            if primitive.volatile {
+                damaged.push(primitive.bounds);
+            } else {
+                previous_indices
+                    .entry(primitive.hash)
+                    .or_default()
+                    .push_back(index);
+            }
+        }
+
+        let mut matched = vec![false; previous.primitives.len()];
+        let mut max_previous_index = None;
+        for primitive in &current.primitives {
+            let previous_index = if primitive.volatile {
+                None
+            } else {
+                previous_indices
+                    .get_mut(&primitive.hash)
+                    .and_then(|indices| indices.pop_front())
+            };
+
+            match previous_index {
+                Some(index) => {
+                    matched[index] = true;
+                    // The primitive itself is unchanged, but if it is now drawn before a primitive
+                    // it was previously drawn after (e.g. a window was brought to the front),
+                    // the overlapping area changes.
+                    if max_previous_index.map_or(false, |max| index < max) {
+                        damaged.push(primitive.bounds);
+                    }
+                    max_previous_index = max_previous_index.max(Some(index));
+                }
+                None => damaged.push(primitive.bounds),
+            }
+        }
+
+        for (primitive, matched) in previous.primitives.iter().zip(matched) {
+            if !matched && !primitive.volatile {
+                damaged.push(primitive.bounds);
+            }
+        }
+
+        let rects = damaged
+            .into_iter()
+            .filter(|rect| rect.is_positive())
+            .filter_map(|rect| IRect::intersect(&to_pixels(rect, pixels_per_point), &full))
+            .collect();
+
+        merge_rects(rects)
+    }
+}
+
+/// Textures whose already visible content changed.
+///
+/// The font atlas is only ever extended by partial updates, so those don't change any glyphs
+/// already on screen.
+fn changed_textures(textures_delta: &TexturesDelta) -> AHashSet<TextureId> {
+    textures_delta
+        .set
+        .iter()
+        .filter(|(id, delta)| delta.pos.is_none() || *id != TextureId::default())
+        .map(|(id, _)| *id)
+        .collect()
+}
+
+fn signature(
+    primitive: &ClippedPrimitive,
+    changed_textures: &AHashSet<TextureId>,
+) -> PrimitiveSignature {
+    let clip_rect = primitive.clip_rect;
+    let mut hasher = AHasher::default();
+    clip_rect.min.x.to_bits().hash(&mut hasher);
+    clip_rect.min.y.to_bits().hash(&mut hasher);
+    clip_rect.max.x.to_bits().hash(&mut hasher);
+    clip_rect.max.y.to_bits().hash(&mut hasher);
+
+    match &primitive.primitive {
+        Primitive::Mesh(mesh) => {
+            mesh.texture_id.hash(&mut hasher);
+            mesh.indices.hash(&mut hasher);
+            hash_vertices(&mesh.vertices, &mut hasher);
+
+            PrimitiveSignature {
+                hash: hasher.finish(),
+                bounds: mesh.calc_bounds().intersect(clip_rect),
+                volatile: changed_textures.contains(&mesh.texture_id),
+            }
+        }
+        Primitive::Callback(callback) => {
+            // Native shadows are drawn from their parameters, other callbacks can draw anything
+            let shadow = callback.callback.downcast_ref::<NativeShadow>();
+            if let Some(shadow) = shadow {
+                shadow.hash(&mut hasher);
+            }
+
+            PrimitiveSignature {
+                hash: hasher.finish(),
+                bounds: callback.rect.intersect(clip_rect),
+                volatile: shadow.is_none(),
+            }
+        }
+    }
+}
+
+fn to_pixels(rect: egui::Rect, pixels_per_point: f32) -> IRect {
+    // Outset by a pixel so anti-aliased edges are covered as well
+    IRect::new(
+        (rect.min.x * pixels_per_point).floor() as i32 - 1,
+        (rect.min.y * pixels_per_point).floor() as i32 - 1,
+        (rect.max.x * pixels_per_point).ceil() as i32 + 1,
+        (rect.max.y * pixels_per_point).ceil() as i32 + 1,
+    )
+}
+
+/// Joins overlapping rects, so no pixel is painted twice.
+fn merge_rects(rects: Vec<IRect>) -> Vec<IRect> {
+    let mut merged: Vec<IRect> = Vec::with_capacity(rects.len());
+
+    for mut rect in rects {
+        while let Some(index) = merged
+            .iter()
+            .position(|other| IRect::intersects(other, &rect))
+        {
+            rect = IRect::join(&rect, &merged.swap_remove(index));
+        }
+        merged.push(rect);
+    }
+
+    if merged.len() > MAX_DAMAGE_RECTS {
+        let joined = merged.iter().fold(IRect::new_empty(), |joined, rect| {
+            IRect::join(&joined, rect)
+        });
+        merged = vec![joined];
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use egui::epaint::{ImageDelta, Mesh};
+    use egui::{Color32, ColorImage, PaintCallback, Pos2, Rect, TextureOptions};
+
+    use super::*;
+
+    const SIZE: ISize = ISize {
+        width: 200,
+        height: 100,
+    };
+
+    fn rect(min: (f32, f32), max: (f32, f32)) -> Rect {
+        Rect::from_min_max(Pos2::new(min.0, min.1), Pos2::new(max.0, max.1))
+    }
+
+    fn mesh(rect: Rect, texture_id: TextureId) -> ClippedPrimitive {
+        let mut mesh = Mesh::with_texture(texture_id);
+        mesh.add_rect_with_uv(
+            rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            Color32::RED,
+        );
+        ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }
+    }
+
+    fn callback(rect: Rect) -> ClippedPrimitive {
+        ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Callback(PaintCallback {
+                rect,
+                callback: Arc::new(()),
+            }),
+        }
+    }
+
+    fn a() -> ClippedPrimitive {
+        mesh(rect((10.0, 10.0), (30.0, 30.0)), TextureId::default())
+    }
+
+    fn b() -> ClippedPrimitive {
+        mesh(rect((20.0, 20.0), (40.0, 40.0)), TextureId::default())
+    }
+
+    /// Damages the whole canvas with `previous`, then returns the damage of `current`.
+    fn damage(previous: &[ClippedPrimitive], current: &[ClippedPrimitive]) -> Vec<IRect> {
+        let mut tracker = DamageTracker::new();
+        tracker.damage(SIZE, 1.0, previous, &TexturesDelta::default());
+        tracker.damage(SIZE, 1.0, current, &TexturesDelta::default())
+    }
+
+    fn pixels(primitive: &ClippedPrimitive) -> IRect {
+        let bounds = match &primitive.primitive {
+            Primitive::Mesh(mesh) => mesh.calc_bounds(),
+            Primitive::Callback(callback) => callback.rect,
+        };
+        to_pixels(bounds, 1.0)
+    }
+
+    fn font_delta(pos: Option<[usize; 2]>) -> (TextureId, ImageDelta) {
+        let image = ColorImage::new([1, 1], Color32::WHITE);
+        let delta = match pos {
+            Some(pos) => ImageDelta::partial(pos, image, TextureOptions::LINEAR),
+            None => ImageDelta::full(image, TextureOptions::LINEAR),
+        };
+        (TextureId::default(), delta)
+    }
+
+    #[test]
+    fn first_frame_damages_everything() {
+        let mut tracker = DamageTracker::new();
+        let damage = tracker.damage(SIZE, 1.0, &[a()], &TexturesDelta::default());
+        assert_eq!(damage, vec![IRect::from_size(SIZE)]);
+    }
+
+    #[test]
+    fn identical_frames_have_no_damage() {
+        assert!(damage(&[a(), b()], &[a(), b()]).is_empty());
+    }
+
+    #[test]
+    fn size_and_scale_changes_damage_everything() {
+        let mut tracker = DamageTracker::new();
+        let delta = TexturesDelta::default();
+        tracker.damage(SIZE, 1.0, &[a()], &delta);
+
+        let larger = ISize::new(300, 100);
+        assert_eq!(
+            tracker.damage(larger, 1.0, &[a()], &delta),
+            vec![IRect::from_size(larger)]
+        );
+        assert_eq!(
+            tracker.damage(larger, 2.0, &[a()], &delta),
+            vec![IRect::from_size(larger)]
+        );
+        assert!(tracker.damage(larger, 2.0, &[a()], &delta).is_empty());
+
+        tracker.reset();
+        assert_eq!(
+            tracker.damage(larger, 2.0, &[a()], &delta),
+            vec![IRect::from_size(larger)]
+        );
+    }
+
+    #[test]
+    fn reordered_primitives_damage_the_one_moved_to_the_front() {
+        // `a` was drawn first and is now drawn over `b`
+        assert_eq!(damage(&[a(), b()], &[b(), a()]), vec![pixels(&a())]);
+    }
+
+    #[test]
+    fn removed_and_added_primitives_damage_their_bounds() {
+        assert_eq!(damage(&[a(), b()], &[a()]), vec![pixels(&b())]);
+        assert_eq!(damage(&[a()], &[a(), b()]), vec![pixels(&b())]);
+    }
+
+    #[test]
+    fn callbacks_are_always_damaged() {
+        let paint_callback = || callback(rect((100.0, 10.0), (120.0, 30.0)));
+        assert_eq!(
+            damage(&[a(), paint_callback()], &[a(), paint_callback()]),
+            vec![pixels(&paint_callback())]
+        );
+        // Also when they are removed
+        assert_eq!(
+            damage(&[a(), paint_callback()], &[a()]),
+            vec![pixels(&paint_callback())]
+        );
+    }
+
+    #[test]
+    fn partial_font_atlas_updates_dont_damage() {
+        let mut tracker = DamageTracker::new();
+        tracker.damage(SIZE, 1.0, &[a()], &TexturesDelta::default());
+
+        let partial = TexturesDelta {
+            set: vec![font_delta(Some([10, 10]))],
+            free: Vec::new(),
+        };
+        assert!(tracker.damage(SIZE, 1.0, &[a()], &partial).is_empty());
+
+        let full = TexturesDelta {
+            set: vec![font_delta(None)],
+            free: Vec::new(),
+        };
+        assert_eq!(tracker.damage(SIZE, 1.0, &[a()], &full), vec![pixels(&a())]);
+    }
+
+    #[test]
+    fn partial_updates_of_other_textures_damage() {
+        let image = ColorImage::new([1, 1], Color32::WHITE);
+        let delta = TexturesDelta {
+            set: vec![
+                font_delta(Some([0, 0])),
+                (
+                    TextureId::User(1),
+                    ImageDelta::partial([0, 0], image, TextureOptions::LINEAR),
+                ),
+            ],
+            free: Vec::new(),
+        };
+        let changed = changed_textures(&delta);
+        assert!(!changed.contains(&TextureId::default()));
+        assert!(changed.contains(&TextureId::User(1)));
+    }
+
+    #[test]
+    fn overlapping_rects_are_merged() {
+        let merged = merge_rects(vec![
+            IRect::new(0, 0, 10, 10),
+            IRect::new(50, 0, 60, 10),
+            IRect::new(5, 5, 20, 20),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert!(merged.contains(&IRect::new(0, 0, 20, 20)));
+        assert!(merged.contains(&IRect::new(50, 0, 60, 10)));
+    }
+
+    #[test]
+    fn too_many_rects_are_joined() {
+        let rects: Vec<IRect> = (0..MAX_DAMAGE_RECTS as i32 + 1)
+            .map(|index| IRect::from_xywh(index * 20, 0, 10, 10))
+            .collect();
+        assert_eq!(
+            merge_rects(rects[..MAX_DAMAGE_RECTS].to_vec()).len(),
+            MAX_DAMAGE_RECTS
+        );
+        assert_eq!(
+            merge_rects(rects),
+            vec![IRect::new(0, 0, MAX_DAMAGE_RECTS as i32 * 20 + 10, 10)]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use egui::epaint::Mesh16;
+use skia_safe::{Canvas, Color, Font, Paint, PaintStyle, Path, Point, Rect};
+
+use crate::painter::egui_rect_to_skia;
+
+/// What the [`crate::Painter`] draws on top of a frame to show what it submitted to the canvas,
+/// see [`crate::EguiSkia::set_debug_overlay`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DebugOverlay {
+    /// The triangles of every mesh, in magenta.
+    pub wireframes: bool,
+    /// The triangles of meshes drawn with remapped white uvs by the raster workaround (see
+    /// [`crate::CpuFix`]), in cyan instead of magenta.
+    pub white_uv_remapped: bool,
+    /// The clip rect of every mesh and callback, in yellow.
+    pub clip_rects: bool,
+    /// The texture id of every mesh, at the top left corner of its bounds.
+    pub texture_ids: bool,
+    /// The rect of every paint callback, in red.
+    pub callback_bounds: bool,
+}
+
+impl DebugOverlay {
+    pub fn all() -> Self {
+        Self {
+            wireframes: true,
+            white_uv_remapped: true,
+            clip_rects: true,
+            texture_ids: true,
+            callback_bounds: true,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+/// The overlay of one call to [`crate::Painter::paint_primitives`], collected while painting and
+/// drawn on top afterwards.
+#[derive(Default)]
+pub(crate) struct DebugShapes {
+    wireframes: Path,
+    remapped_wireframes: Path,
+    clip_rects: Vec<Rect>,
+    texture_ids: Vec<(Point, String)>,
+    callback_bounds: Vec<Rect>,
+}
+
+impl DebugShapes {
+    pub fn add_mesh(
+        &mut self,
+        overlay: &DebugOverlay,
+        mesh: &Mesh16,
+        clip_rect: egui::Rect,
+        remapped_white_uv: bool,
+    ) {
+        let wireframes = if remapped_white_uv && overlay.white_uv_remapped {
+            Some(&mut self.remapped_wireframes)
+        } else if overlay.wireframes {
+            Some(&mut self.wireframes)
+        } else {
+            None
+        };
+        if let Some(path) = wireframes {
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]]
+                    .map(|index| mesh.vertices[index as usize].pos)
+                    .map(|pos| Point::new(pos.x, pos.y));
+                path.add_poly(&[a, b, c], true);
+            }
+        }
+
+        if overlay.clip_rects {
+            self.add_clip_rect(clip_rect);
+        }
+
+        if overlay.texture_ids {
+            let min = mesh
+                .vertices
+                .iter()
+                .fold(egui::pos2(f32::INFINITY, f32::INFINITY), |min, vertex| {
+                    min.min(vertex.pos)
+                });
+            if min.x.is_finite() && min.y.is_finite() {
+                let label = format!("{:?}", mesh.texture_id);
+                self.texture_ids.push((Point::new(min.x, min.y), label));
+            }
+        }
+    }
+
+    pub fn add_callback(
+        &mut self,
+        overlay: &DebugOverlay,
+        rect: egui::Rect,
+        clip_rect: egui::Rect,
+    ) {
+        if overlay.callback_bounds {
+            self.callback_bounds.push(egui_rect_to_skia(rect));
+        }
+        if overlay.clip_rects {
+            self.add_clip_rect(clip_rect);
+        }
+    }
+
+    fn add_clip_rect(&mut self, clip_rect: egui::Rect) {
+        let clip_rect = egui_rect_to_skia(clip_rect);
+        // Consecutive primitives usually share their clip rect
+        if self.clip_rects.last() != Some(&clip_rect) {
+            self.clip_rects.push(clip_rect);
+        }
+    }
+
+    /// Draws the overlay onto a canvas scaled to points.
+    pub fn draw(&self, canvas: &mut Canvas) {
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_style(PaintStyle::Stroke);
+        // Hairlines stay one pixel wide at any scale
+        paint.set_stroke_width(0.0);
+
+        paint.set_color(Color::from_argb(160, 255, 0, 255));
+        canvas.draw_path(&self.wireframes, &paint);
+
+        paint.set_color(Color::from_argb(200, 0, 255, 255));
+        canvas.draw_path(&self.remapped_wireframes, &paint);
+
+        paint.set_color(Color::from_argb(200, 255, 255, 0));
+        for clip_rect in &self.clip_rects {
+            canvas.draw_rect(clip_rect, &paint);
+        }
+
+        paint.set_color(Color::from_argb(220, 255, 40, 40));
+        for rect in &self.callback_bounds {
+            canvas.draw_rect(rect, &paint);
+        }
+
+        paint.set_style(PaintStyle::Fill);
+        paint.set_color(Color::from_rgb(255, 255, 255));
+        let mut font = Font::default();
+        font.set_size(9.0);
+        for (point, label) in &self.texture_ids {
+            canvas.draw_str(label, *point + Point::new(1.0, 9.0), &font, &paint);
+        }
+    }
+}
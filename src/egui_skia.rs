@@ -1,15 +1,27 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
-use egui::{Context, Pos2};
-use skia_safe::{Canvas, Surface};
+use egui::{ClippedPrimitive, Context, LayerId, Pos2, TexturesDelta};
+use skia_safe::canvas::SaveLayerRec;
+use skia_safe::{Canvas, ClipOp, Color, IRect, Paint, Region, Surface};
 
-use crate::painter::Painter;
+use crate::damage::DamageTracker;
+use crate::debug_overlay::DebugOverlay;
+use crate::layers::{add_layer_markers, remove_layer_markers, split_layers, LayerStyle};
+use crate::painter::{CpuFix, Painter, PainterOptions};
+use crate::shadows::replace_shadows;
 
 pub struct RasterizeOptions {
     pub pixels_per_point: f32,
     /// The number of frames to render before a screenshot is taken.
     /// Default is 2, so egui will be able to display windows
     pub frames_before_screenshot: usize,
+    /// Whether to apply the raster workaround, see [`CpuFix`].
+    pub cpu_fix: CpuFix,
+    pub painter_options: PainterOptions,
+    /// Draws a debug overlay on top of the output, see [`EguiSkia::set_debug_overlay`].
+    pub debug_overlay: DebugOverlay,
 }
 
 impl Default for RasterizeOptions {
@@ -17,6 +29,9 @@ impl Default for RasterizeOptions {
         Self {
             pixels_per_point: 1.0,
             frames_before_screenshot: 2,
+            cpu_fix: CpuFix::default(),
+            painter_options: PainterOptions::default(),
+            debug_overlay: DebugOverlay::default(),
         }
     }
 }
@@ -39,8 +54,14 @@ pub fn draw_onto_surface(
     let RasterizeOptions {
         pixels_per_point,
         frames_before_screenshot,
+        cpu_fix,
+        painter_options,
+        debug_overlay,
     } = options.unwrap_or_default();
     let mut backend = EguiSkia::new();
+    backend.painter = Painter::new_with_options(painter_options);
+    backend.painter.set_cpu_fix(cpu_fix);
+    backend.set_debug_overlay(debug_overlay);
 
     let input = egui::RawInput {
         screen_rect: Some(
@@ -60,6 +81,40 @@ pub fn draw_onto_surface(
     backend.paint(surface.canvas());
 }
 
+/// Everything needed to paint a frame, returned by [`EguiSkia::run_detached`].
+///
+/// It doesn't borrow from the [`Context`], so it can be sent to a render thread and painted there
+/// with [`Painter::paint_frame`] while the ui thread runs the next frame. The shapes are already
+/// tessellated, as tessellation needs the fonts of the context.
+///
+/// Clone it to paint the same frame with several painters sharing a
+/// [`crate::TextureStore`], see [`Painter::paint_frame`].
+#[derive(Clone)]
+pub struct FrameOutput {
+    pub clipped_primitives: Vec<ClippedPrimitive>,
+    pub textures_delta: TexturesDelta,
+    pub pixels_per_point: f32,
+    /// Set by the first painter that uploads the textures, shared with the clones of the frame.
+    pub(crate) textures_uploaded: Arc<AtomicBool>,
+}
+
+impl FrameOutput {
+    /// Replaces this frame with a newer one, keeping the texture updates of both.
+    ///
+    /// Use this to skip frames when the render thread falls behind: the textures delta of every
+    /// frame has to reach the painter, or it will be missing textures.
+    pub fn append(&mut self, newer: FrameOutput) {
+        self.clipped_primitives = newer.clipped_primitives;
+        if self.textures_uploaded.load(Ordering::Acquire) {
+            self.textures_delta = newer.textures_delta;
+        } else {
+            self.textures_delta.append(newer.textures_delta);
+        }
+        self.pixels_per_point = newer.pixels_per_point;
+        self.textures_uploaded = newer.textures_uploaded;
+    }
+}
+
 /// Convenience wrapper for using [`egui`] from a [`skia`] app.
 pub struct EguiSkia {
     pub egui_ctx: Context,
@@ -67,6 +122,11 @@ pub struct EguiSkia {
 
     shapes: Vec<egui::epaint::ClippedShape>,
     textures_delta: egui::TexturesDelta,
+    damage_tracker: Option<DamageTracker>,
+    layer_painting: bool,
+    /// Whether [`Self::run`] added layer markers to `shapes`, which can differ from
+    /// `layer_painting` if it is changed before the shapes are painted.
+    layer_markers: bool,
 }
 
 impl EguiSkia {
@@ -77,6 +137,46 @@ impl EguiSkia {
             painter,
             shapes: Default::default(),
             textures_delta: Default::default(),
+            damage_tracker: None,
+            layer_painting: false,
+            layer_markers: false,
+        }
+    }
+
+    /// Enables or disables damage tracking.
+    ///
+    /// With damage tracking enabled, [`Self::paint`] compares the frame against the previously
+    /// painted one and only repaints the regions that changed. The canvas has to keep the content
+    /// of the previous frame and must not be cleared by the host, as the painter clears the
+    /// damaged regions itself.
+    pub fn set_damage_tracking(&mut self, enabled: bool) {
+        self.damage_tracker = enabled.then(DamageTracker::new);
+    }
+
+    /// Enables or disables painting layer by layer with [`Self::paint_layers`].
+    ///
+    /// To split the shapes into layers, [`Self::run`] adds a marker to every layer. egui keeps
+    /// the shape lists of layers that had markers around, so this is disabled by default.
+    pub fn set_layer_painting(&mut self, enabled: bool) {
+        self.layer_painting = enabled;
+    }
+
+    /// Makes the next call to [`Self::paint`] repaint the whole canvas, e.g. after the host
+    /// drew over it or the surface was recreated.
+    pub fn invalidate(&mut self) {
+        if let Some(damage_tracker) = &mut self.damage_tracker {
+            damage_tracker.reset();
+        }
+    }
+
+    /// Sets the debug overlay of the painter, see [`Painter::set_debug_overlay`].
+    ///
+    /// With damage tracking, changing the overlay repaints the whole canvas on the next call to
+    /// [`Self::paint`], so it's also added to or removed from the regions that didn't change.
+    pub fn set_debug_overlay(&mut self, debug_overlay: DebugOverlay) {
+        if self.painter.debug_overlay() != debug_overlay {
+            self.painter.set_debug_overlay(debug_overlay);
+            self.invalidate();
         }
     }
 
@@ -85,36 +185,162 @@ impl EguiSkia {
     /// Call [`Self::paint`] later to paint.
     pub fn run(
         &mut self,
-        input: egui::RawInput,
-        run_ui: impl FnMut(&Context),
+        mut input: egui::RawInput,
+        mut run_ui: impl FnMut(&Context),
     ) -> (Duration, egui::PlatformOutput) {
+        if input.max_texture_side.is_none() {
+            input.max_texture_side = Some(self.painter.max_texture_side());
+        }
+
+        let layer_markers = self.layer_painting;
         let egui::FullOutput {
             platform_output,
             textures_delta,
             shapes,
             repaint_after,
-        } = self.egui_ctx.run(input, run_ui);
+        } = self.egui_ctx.run(input, |ctx| {
+            run_ui(ctx);
+            if layer_markers {
+                add_layer_markers(ctx);
+            }
+        });
 
         self.shapes = shapes;
+        self.layer_markers = layer_markers;
         self.textures_delta.append(textures_delta);
 
         (repaint_after, platform_output)
     }
 
+    /// Like [`Self::run`], but returns the frame instead of keeping it for [`Self::paint`].
+    ///
+    /// The [`FrameOutput`] can be painted by any [`Painter`] using the textures of this instance,
+    /// e.g. one moved to a render thread. Frames have to be painted in order.
+    pub fn run_detached(
+        &mut self,
+        input: egui::RawInput,
+        run_ui: impl FnMut(&Context),
+    ) -> (Duration, egui::PlatformOutput, FrameOutput) {
+        let (repaint_after, platform_output) = self.run(input, run_ui);
+        (repaint_after, platform_output, self.take_frame_output())
+    }
+
+    fn take_frame_output(&mut self) -> FrameOutput {
+        let mut shapes = std::mem::take(&mut self.shapes);
+        if self.layer_markers {
+            remove_layer_markers(&mut shapes);
+        }
+        if self.painter.options().native_shadows {
+            replace_shadows(&mut shapes);
+        }
+        FrameOutput {
+            clipped_primitives: self.egui_ctx.tessellate(shapes),
+            textures_delta: std::mem::take(&mut self.textures_delta),
+            pixels_per_point: self.egui_ctx.pixels_per_point(),
+            textures_uploaded: Arc::default(),
+        }
+    }
+
     /// Paint the results of the last call to [`Self::run`].
-    pub fn paint(&mut self, canvas: &mut Canvas) {
+    ///
+    /// Returns the regions of the canvas that were repainted, in pixels. Without damage tracking
+    /// (see [`Self::set_damage_tracking`]) this is always the whole canvas.
+    pub fn paint(&mut self, canvas: &mut Canvas) -> Vec<IRect> {
+        let frame = self.take_frame_output();
+
+        let size = canvas.base_layer_size();
+        let damage = match &mut self.damage_tracker {
+            Some(damage_tracker) => damage_tracker.damage(
+                size,
+                frame.pixels_per_point,
+                &frame.clipped_primitives,
+                &frame.textures_delta,
+            ),
+            None => vec![IRect::from_size(size)],
+        };
+
+        if self.damage_tracker.is_some() {
+            let mut region = Region::new();
+            region.set_rects(&damage);
+
+            canvas.save();
+            canvas.reset_matrix();
+            canvas.clip_region(&region, ClipOp::Intersect);
+            canvas.clear(Color::TRANSPARENT);
+        }
+
+        self.painter.paint_frame(canvas, frame);
+
+        if self.damage_tracker.is_some() {
+            canvas.restore();
+        }
+
+        damage
+    }
+
+    /// Paints the results of the last call to [`Self::run`] layer by layer, in egui's paint
+    /// order (background, middle, foreground, tooltip and debug layers).
+    ///
+    /// `before_layer` is called before each layer with shapes is painted. It can draw host
+    /// content below the layer, e.g. a game world between egui's background panels and its
+    /// windows, and returns how the layer is composited. Draw content above all layers after
+    /// this returns. The whole canvas is repainted, damage tracking isn't used.
+    ///
+    /// Needs [`Self::set_layer_painting`] to be enabled before the call to [`Self::run`],
+    /// otherwise all shapes are painted as a single background layer.
+    pub fn paint_layers(
+        &mut self,
+        canvas: &mut Canvas,
+        mut before_layer: impl FnMut(&mut Canvas, LayerId) -> LayerStyle,
+    ) {
         let shapes = std::mem::take(&mut self.shapes);
         let textures_delta = std::mem::take(&mut self.textures_delta);
-        let clipped_primitives = self.egui_ctx.tessellate(shapes);
-        self.painter.paint_and_update_textures(
-            canvas,
-            self.egui_ctx.pixels_per_point(),
-            clipped_primitives,
-            textures_delta,
-        );
+        let pixels_per_point = self.egui_ctx.pixels_per_point();
+        // The host draws between the layers, so the next frame can't be compared to this one
+        self.invalidate();
+
+        self.painter
+            .begin_frame(canvas, &textures_delta, &AtomicBool::new(false));
+
+        let layers = if self.layer_markers {
+            split_layers(shapes)
+        } else {
+            vec![(LayerId::background(), shapes)]
+        };
+
+        for (layer_id, mut shapes) in layers {
+            let save_count = canvas.save();
+            let style = before_layer(canvas, layer_id);
+            canvas.restore_to_count(save_count);
+
+            if self.painter.options().native_shadows {
+                replace_shadows(&mut shapes);
+            }
+            let primitives = self.egui_ctx.tessellate(shapes);
+
+            let save_count = canvas.save();
+            if style.needs_save_layer() {
+                let mut paint = Paint::default();
+                paint.set_alpha_f(style.opacity);
+                paint.set_blend_mode(style.blend_mode);
+                canvas.save_layer(&SaveLayerRec::default().paint(&paint));
+            }
+            self.painter
+                .paint_primitives(canvas, pixels_per_point, primitives);
+            canvas.restore_to_count(save_count);
+        }
+
+        self.painter.end_frame();
     }
 }
 
+// The painter and frames are moved to render threads.
+const _: fn() = || {
+    fn assert_send<T: Send>() {}
+    assert_send::<Painter>();
+    assert_send::<FrameOutput>();
+};
+
 impl Default for EguiSkia {
     fn default() -> Self {
         Self::new()
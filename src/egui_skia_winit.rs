@@ -1,14 +1,19 @@
 use std::time::Duration;
 
-use egui::Context;
+use egui::{Context, LayerId};
 pub use egui_winit;
 use egui_winit::winit::event_loop::EventLoopWindowTarget;
 use egui_winit::winit::window::Window;
 use egui_winit::EventResponse;
-use skia_safe::Canvas;
+use skia_safe::{Canvas, IRect};
 
-use crate::EguiSkia;
+use crate::{EguiSkia, LayerStyle};
 
+/// Drives egui for a single winit window.
+///
+/// egui's viewports, which pop windows out into native windows of their own, need egui 0.24 or
+/// newer and aren't supported. To show the same context in several windows, see
+/// [`crate::TextureStore`].
 pub struct EguiSkiaWinit {
     pub egui_skia: EguiSkia,
     pub egui_winit: egui_winit::State,
@@ -48,7 +53,18 @@ impl EguiSkiaWinit {
     }
 
     /// Paint the results of the last call to [`Self::run`].
-    pub fn paint(&mut self, canvas: &mut Canvas) {
-        self.egui_skia.paint(canvas);
+    ///
+    /// Returns the repainted regions of the canvas, see [`EguiSkia::paint`].
+    pub fn paint(&mut self, canvas: &mut Canvas) -> Vec<IRect> {
+        self.egui_skia.paint(canvas)
+    }
+
+    /// See [`EguiSkia::paint_layers`].
+    pub fn paint_layers(
+        &mut self,
+        canvas: &mut Canvas,
+        before_layer: impl FnMut(&mut Canvas, LayerId) -> LayerStyle,
+    ) {
+        self.egui_skia.paint_layers(canvas, before_layer)
     }
 }
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use egui::{
+    Color32, FontData, FontDefinitions, FontFamily, PaintCallback, Response, Sense, Ui, Vec2,
+};
+use skia_safe::{Font, FontMgr, FontStyle, Paint, Picture, PictureRecorder, Point, Rect, Typeface};
+
+use crate::painter::unpremultiply;
+use crate::EguiSkiaPaintCallback;
+
+/// Builds [`FontDefinitions`] from fonts found by a skia [`FontMgr`], e.g. the fonts installed
+/// on the system or fonts bundled with the app.
+///
+/// Fonts are added to the fallback chain of a family in the order they are pushed. egui's
+/// default fonts are appended to every chain by [`Self::build`], so glyphs missing from all
+/// pushed fonts are still found there.
+pub struct FontDefinitionsBuilder {
+    font_mgr: FontMgr,
+    definitions: FontDefinitions,
+}
+
+impl FontDefinitionsBuilder {
+    /// Uses the fonts installed on the system.
+    pub fn new() -> Self {
+        Self::with_font_mgr(FontMgr::new())
+    }
+
+    pub fn with_font_mgr(font_mgr: FontMgr) -> Self {
+        let mut definitions = FontDefinitions::empty();
+        definitions
+            .families
+            .insert(FontFamily::Proportional, Vec::new());
+        definitions
+            .families
+            .insert(FontFamily::Monospace, Vec::new());
+        Self {
+            font_mgr,
+            definitions,
+        }
+    }
+
+    pub fn font_mgr(&self) -> &FontMgr {
+        &self.font_mgr
+    }
+
+    /// Adds the font family `name` to the fallback chain of `family`.
+    ///
+    /// Returns `false` if the font manager doesn't know the family.
+    pub fn push_family(&mut self, family: FontFamily, name: &str) -> bool {
+        match self.font_mgr.match_family_style(name, FontStyle::normal()) {
+            Some(typeface) => self.push_typeface(family, &typeface),
+            None => false,
+        }
+    }
+
+    /// Adds the font that the font manager falls back to for `character` to the fallback chain
+    /// of `family`, e.g. to find a font for CJK text.
+    ///
+    /// Returns `false` if no font contains the character.
+    pub fn push_fallback_for(&mut self, family: FontFamily, character: char) -> bool {
+        match self.font_mgr.match_family_style_character(
+            "",
+            FontStyle::normal(),
+            &[],
+            character as i32,
+        ) {
+            Some(typeface) => self.push_typeface(family, &typeface),
+            None => false,
+        }
+    }
+
+    /// Adds a font file, e.g. one bundled with the app, to the fallback chain of `family`.
+    pub fn push_font_data(&mut self, family: FontFamily, name: &str, data: Vec<u8>) {
+        self.push(family, name.to_owned(), FontData::from_owned(data));
+    }
+
+    /// Adds a typeface, e.g. one loaded with [`FontMgr::new_from_data`], to the fallback chain of
+    /// `family`.
+    ///
+    /// Returns `false` if the typeface has no font data egui can load.
+    pub fn push_typeface(&mut self, family: FontFamily, typeface: &Typeface) -> bool {
+        let (data, index) = match typeface.to_font_data() {
+            Some(font_data) => font_data,
+            None => return false,
+        };
+        let name = format!("{}#{}", typeface.family_name(), index);
+        let mut font_data = FontData::from_owned(data);
+        font_data.index = index as u32;
+        self.push(family, name, font_data);
+        true
+    }
+
+    fn push(&mut self, family: FontFamily, name: String, font_data: FontData) {
+        let fonts = self.definitions.families.entry(family).or_default();
+        if !fonts.contains(&name) {
+            fonts.push(name.clone());
+        }
+        self.definitions.font_data.insert(name, font_data);
+    }
+
+    pub fn build(mut self) -> FontDefinitions {
+        let defaults = FontDefinitions::default();
+        self.definitions.font_data.extend(defaults.font_data);
+        for (family, default_fonts) in defaults.families {
+            let fonts = self.definitions.families.entry(family).or_default();
+            for font in default_fonts {
+                if !fonts.contains(&font) {
+                    fonts.push(font);
+                }
+            }
+        }
+        self.definitions
+    }
+}
+
+impl Default for FontDefinitionsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text drawn by skia instead of egui, for glyphs egui's font atlas can't hold.
+///
+/// egui's atlas only stores coverage, so color glyphs (COLR or CBDT emoji) lose their colors.
+/// This draws each character with the font the font manager picks for it, or with one of the
+/// typefaces passed to [`Self::with_typefaces`], so color fonts keep their colors. The text
+/// isn't shaped: emoji sequences joined with zero width joiners are shown as their parts.
+#[derive(Clone)]
+pub struct ColorText {
+    picture: Picture,
+    size: Vec2,
+}
+
+impl ColorText {
+    /// Lays out the text for a font size and color in points. The color is used for glyphs
+    /// without colors of their own.
+    pub fn new(font_mgr: &FontMgr, text: &str, font_size: f32, color: Color32) -> Self {
+        Self::with_typefaces(&[], font_mgr, text, font_size, color)
+    }
+
+    /// Like [`Self::new`], but draws each character with the first of `typefaces` that has a
+    /// glyph for it, e.g. fonts bundled with the app. Other characters use the font the font
+    /// manager picks.
+    pub fn with_typefaces(
+        typefaces: &[Typeface],
+        font_mgr: &FontMgr,
+        text: &str,
+        font_size: f32,
+        color: Color32,
+    ) -> Self {
+        let mut runs: Vec<(Typeface, String)> = Vec::new();
+        for character in text.chars() {
+            let typeface = typefaces
+                .iter()
+                .find(|typeface| typeface.unichar_to_glyph(character as i32) != 0)
+                .cloned()
+                .or_else(|| {
+                    font_mgr.match_family_style_character(
+                        "",
+                        FontStyle::normal(),
+                        &[],
+                        character as i32,
+                    )
+                })
+                .unwrap_or_default();
+            match runs.last_mut() {
+                Some((run_typeface, run)) if run_typeface.unique_id() == typeface.unique_id() => {
+                    run.push(character);
+                }
+                _ => runs.push((typeface, character.to_string())),
+            }
+        }
+        let runs: Vec<(Font, String)> = runs
+            .into_iter()
+            .map(|(typeface, run)| (Font::new(typeface, font_size), run))
+            .collect();
+
+        let (ascent, descent) =
+            runs.iter()
+                .fold((0.0f32, 0.0f32), |(ascent, descent), (font, _)| {
+                    let (_, metrics) = font.metrics();
+                    (ascent.max(-metrics.ascent), descent.max(metrics.descent))
+                });
+        let width: f32 = runs
+            .iter()
+            .map(|(font, run)| font.measure_str(run, None).0)
+            .sum();
+        let size = egui::vec2(width.ceil(), (ascent + descent).ceil());
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(unpremultiply(color));
+
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_wh(size.x, size.y), None);
+        let mut x = 0.0;
+        for (font, run) in &runs {
+            canvas.draw_str(run, Point::new(x, ascent), font, &paint);
+            x += font.measure_str(run, None).0;
+        }
+
+        Self {
+            picture: recorder.finish_recording_as_picture(None).unwrap(),
+            size,
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    pub fn show(&self, ui: &mut Ui) -> Response {
+        let (rect, response) = ui.allocate_exact_size(self.size, Sense::hover());
+
+        if ui.is_rect_visible(rect) {
+            let picture = self.picture.clone();
+            ui.painter().add(PaintCallback {
+                rect,
+                callback: Arc::new(EguiSkiaPaintCallback::new(move |canvas| {
+                    canvas.draw_picture(&picture, None, None);
+                })),
+            });
+        }
+
+        response
+    }
+}
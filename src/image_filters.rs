@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use egui::{PaintCallback, Shape, Ui};
+use skia_safe::canvas::SaveLayerRec;
+use skia_safe::{image_filters, Canvas, ClipOp, ImageFilter, Paint, RRect, TileMode};
+
+use crate::painter::egui_rect_to_skia;
+
+enum Effect {
+    Backdrop { filter: ImageFilter, rounding: f32 },
+    Begin { filter: ImageFilter },
+    End,
+}
+
+/// Applies a skia [`ImageFilter`] (e.g. from [`skia_safe::image_filters`]: a blur, drop shadow or
+/// color matrix) to parts of the ui. Add the returned shapes to a layer's painter, the painter
+/// applies the filter when it reaches them.
+///
+/// `rect` is the area affected by the filter, including e.g. the offset of a drop shadow. Parts
+/// of the filter's output outside of it may not be repainted with damage tracking.
+pub struct ImageFilterEffect {
+    effect: Effect,
+}
+
+impl ImageFilterEffect {
+    /// Filters what was painted behind `rect` before this shape, e.g. to blur the content
+    /// behind a translucent window. Add it before the window's background.
+    pub fn backdrop(rect: egui::Rect, rounding: f32, filter: ImageFilter) -> Shape {
+        Self::shape(rect, Effect::Backdrop { filter, rounding })
+    }
+
+    /// A frosted glass backdrop, blurring what was painted behind `rect` with `sigma` in points.
+    pub fn backdrop_blur(rect: egui::Rect, rounding: f32, sigma: f32) -> Shape {
+        let filter = image_filters::blur((sigma, sigma), TileMode::Clamp, None, None)
+            .expect("Failed to create blur filter");
+        Self::backdrop(rect, rounding, filter)
+    }
+
+    /// Starts applying the filter to everything painted until the next [`Self::end`].
+    pub fn begin(rect: egui::Rect, filter: ImageFilter) -> Shape {
+        Self::shape(rect, Effect::Begin { filter })
+    }
+
+    /// Ends the filter started by the last [`Self::begin`]. Pass the same rect to both, so egui
+    /// doesn't skip one of them.
+    pub fn end(rect: egui::Rect) -> Shape {
+        Self::shape(rect, Effect::End)
+    }
+
+    fn shape(rect: egui::Rect, effect: Effect) -> Shape {
+        Shape::Callback(PaintCallback {
+            rect,
+            callback: Arc::new(ImageFilterEffect { effect }),
+        })
+    }
+
+    /// Applies the effect to a canvas scaled to points. `open_layers` counts the layers started
+    /// by [`Self::begin`] that haven't been ended yet.
+    pub(crate) fn apply(
+        &self,
+        canvas: &mut Canvas,
+        clip_rect: egui::Rect,
+        rect: egui::Rect,
+        open_layers: &mut usize,
+    ) {
+        match &self.effect {
+            Effect::Backdrop { filter, rounding } => {
+                let rect = egui_rect_to_skia(rect);
+                let mut arc = skia_safe::AutoCanvasRestore::guard(canvas, true);
+                arc.clip_rect(egui_rect_to_skia(clip_rect), ClipOp::default(), true);
+                arc.clip_rrect(
+                    RRect::new_rect_xy(rect, *rounding, *rounding),
+                    ClipOp::default(),
+                    true,
+                );
+                // Restoring the layer right away draws the filtered backdrop back in place.
+                arc.save_layer(&SaveLayerRec::default().bounds(&rect).backdrop(filter));
+                arc.restore();
+            }
+            Effect::Begin { filter } => {
+                let mut paint = Paint::default();
+                paint.set_image_filter(filter.clone());
+                canvas.save_layer(&SaveLayerRec::default().paint(&paint));
+                *open_layers += 1;
+            }
+            Effect::End => {
+                if *open_layers > 0 {
+                    canvas.restore();
+                    *open_layers -= 1;
+                }
+            }
+        }
+    }
+}
+
+/// Applies the filter to everything `add_contents` adds to the ui.
+pub fn with_image_filter<R>(
+    ui: &mut Ui,
+    filter: ImageFilter,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> R {
+    let begin = ui.painter().add(Shape::Noop);
+    let result = add_contents(ui);
+
+    let rect = ui.clip_rect();
+    ui.painter()
+        .set(begin, ImageFilterEffect::begin(rect, filter));
+    ui.painter().add(ImageFilterEffect::end(rect));
+
+    result
+}
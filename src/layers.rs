@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use egui::epaint::ClippedShape;
+use egui::{Context, LayerId, PaintCallback, Shape};
+use skia_safe::BlendMode;
+
+/// How a layer is composited onto the layers below it, returned by the callback passed to
+/// [`crate::EguiSkia::paint_layers`].
+#[derive(Clone, Copy, Debug)]
+pub struct LayerStyle {
+    /// Defaults to 1.0.
+    pub opacity: f32,
+    /// Defaults to [`BlendMode::SrcOver`].
+    pub blend_mode: BlendMode,
+}
+
+impl Default for LayerStyle {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            blend_mode: BlendMode::SrcOver,
+        }
+    }
+}
+
+impl LayerStyle {
+    /// Whether the layer has to be painted into an offscreen layer to apply the style.
+    pub(crate) fn needs_save_layer(&self) -> bool {
+        self.opacity < 1.0 || self.blend_mode != BlendMode::SrcOver
+    }
+}
+
+/// Added as the last shape of every layer, so the shapes egui returns in paint order can be
+/// split into layers again.
+struct LayerEnd(LayerId);
+
+/// Adds a [`LayerEnd`] to the layers that can have shapes this frame.
+pub(crate) fn add_layer_markers(ctx: &Context) {
+    let mut layer_ids: Vec<LayerId> = ctx.memory(|memory| memory.layer_ids().collect());
+    layer_ids.push(LayerId::background());
+    layer_ids.push(LayerId::debug());
+
+    let rect = ctx.screen_rect();
+    for layer_id in layer_ids {
+        ctx.layer_painter(layer_id).add(PaintCallback {
+            rect,
+            callback: Arc::new(LayerEnd(layer_id)),
+        });
+    }
+}
+
+fn layer_end(shape: &Shape) -> Option<LayerId> {
+    match shape {
+        Shape::Callback(callback) => callback
+            .callback
+            .downcast_ref::<LayerEnd>()
+            .map(|layer_end| layer_end.0),
+        _ => None,
+    }
+}
+
+pub(crate) fn remove_layer_markers(shapes: &mut Vec<ClippedShape>) {
+    shapes.retain(|ClippedShape(_, shape)| layer_end(shape).is_none());
+}
+
+/// Splits the shapes into the layers that have any, in paint order. Shapes of layers without a
+/// marker are grouped with the next layer.
+pub(crate) fn split_layers(shapes: Vec<ClippedShape>) -> Vec<(LayerId, Vec<ClippedShape>)> {
+    let mut layers = Vec::new();
+    let mut layer_shapes = Vec::new();
+
+    for clipped_shape in shapes {
+        match layer_end(&clipped_shape.1) {
+            Some(layer_id) => {
+                if !layer_shapes.is_empty() {
+                    layers.push((layer_id, std::mem::take(&mut layer_shapes)));
+                }
+            }
+            None => layer_shapes.push(clipped_shape),
+        }
+    }
+
+    // Shapes after the last marker
+    if !layer_shapes.is_empty() {
+        layers.push((LayerId::debug(), layer_shapes));
+    }
+
+    layers
+}
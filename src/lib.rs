@@ -1,12 +1,43 @@
 extern crate core;
 
+mod damage;
+mod debug_overlay;
 mod egui_skia;
+mod fonts;
+mod image_filters;
+mod layers;
 mod painter;
+mod runtime_effect;
+mod shadows;
+mod texture_budget;
+mod texture_store;
+mod texture_tiles;
+mod vertex_cache;
 
 #[cfg(feature = "winit")]
 mod egui_skia_winit;
 #[cfg(feature = "winit")]
 pub use egui_skia_winit::EguiSkiaWinit;
 
+#[cfg(feature = "svg")]
+mod svg;
+#[cfg(feature = "svg")]
+pub use svg::SvgImage;
+
+#[cfg(feature = "textlayout")]
+mod textlayout;
+#[cfg(feature = "textlayout")]
+pub use textlayout::{system_font_collection, ParagraphLabel};
+
+pub use debug_overlay::DebugOverlay;
 pub use egui_skia::*;
-pub use painter::EguiSkiaPaintCallback;
+pub use fonts::{ColorText, FontDefinitionsBuilder};
+pub use image_filters::{with_image_filter, ImageFilterEffect};
+pub use layers::LayerStyle;
+pub use painter::{
+    CpuFix, EguiSkiaPaintCallback, FontAtlasFormat, PaintStats, Painter, PainterOptions,
+    TextureWrapMode,
+};
+pub use runtime_effect::ShaderFill;
+pub use texture_budget::{TextureBudgetExceeded, TextureUsage};
+pub use texture_store::TextureStore;
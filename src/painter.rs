@@ -1,45 +1,486 @@
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use egui::epaint::ahash::AHashMap;
-#[cfg(feature = "cpu_fix")]
-use egui::epaint::Mesh16;
-use egui::epaint::Primitive;
-use egui::{ClippedPrimitive, ImageData, Pos2, TextureId, TexturesDelta};
-use skia_safe::vertices::VertexMode;
+use egui::epaint::{Mesh16, Primitive, WHITE_UV};
+use egui::{ClippedPrimitive, Color32, ImageData, PaintCallback, TextureId, TexturesDelta};
+use skia_safe::image::CachingHint;
+use skia_safe::shaders;
+use skia_safe::vertices::{self, BuilderFlags, VertexMode};
 use skia_safe::{
     scalar, BlendMode, Canvas, ClipOp, Color, ConditionallySend, Data, Drawable, Image, ImageInfo,
-    Paint, PictureRecorder, Point, Rect, Sendable, Surface, Vertices,
+    Paint, PictureRecorder, Point, Rect, Sendable, Shader, Surface, Vertices,
 };
 
+use crate::debug_overlay::{DebugOverlay, DebugShapes};
+use crate::egui_skia::FrameOutput;
+use crate::image_filters::ImageFilterEffect;
+use crate::shadows::NativeShadow;
+use crate::texture_budget::{TextureBudget, TextureBudgetExceeded, TextureUsage};
+use crate::texture_store::{TextureStore, Textures};
+use crate::texture_tiles::{assemble_tiles, split_into_tiles, split_mesh, Tile};
+use crate::vertex_cache::VertexCache;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 #[derive(Eq, PartialEq)]
 enum PaintType {
     Image,
     Font,
 }
 
-struct PaintHandle {
+pub(crate) struct PaintHandle {
     paint: Paint,
-    image: Image,
+    /// Paint for font textures on raster canvases with the cpu fix enabled, which draws
+    /// everything outside of the texture white, see [`WHITE_UV_OFFSET`].
+    white_uv_paint: Option<Paint>,
+    /// `None` for images larger than `max_texture_side`, which are only kept as `tiles`.
+    image: Option<Image>,
     paint_type: PaintType,
+    sampling_options: skia_safe::SamplingOptions,
+    wrap_mode: TextureWrapMode,
+    last_used_frame: u64,
+    max_texture_side: usize,
+    /// Tiles of images larger than `max_texture_side`, see [`split_into_tiles`]. Meshes using
+    /// them are drawn once per tile instead of with `paint`.
+    tiles: Vec<Tile>,
+}
+
+impl PaintHandle {
+    fn new(
+        image: Image,
+        paint_type: PaintType,
+        sampling_options: skia_safe::SamplingOptions,
+        wrap_mode: TextureWrapMode,
+        max_texture_side: usize,
+    ) -> Self {
+        let mut handle = Self {
+            paint: Paint::default(),
+            white_uv_paint: None,
+            image: Some(image),
+            paint_type,
+            sampling_options,
+            wrap_mode,
+            last_used_frame: 0,
+            max_texture_side,
+            tiles: Vec::new(),
+        };
+        handle.update_paints();
+        handle
+    }
+
+    /// Approximate size of the image's or its tiles' pixels in bytes.
+    fn bytes(&self) -> usize {
+        self.image
+            .iter()
+            .chain(self.tiles.iter().map(|tile| &tile.image))
+            .map(|image| {
+                let info = image.image_info();
+                let bytes = info.width() as usize * info.height() as usize * info.bytes_per_pixel();
+                if image.has_mipmaps() {
+                    // The mip levels add up to a third of the base level
+                    bytes * 4 / 3
+                } else {
+                    bytes
+                }
+            })
+            .sum()
+    }
+
+    /// (Re-)creates the shaders of the paints from the image and texture options.
+    fn update_paints(&mut self) {
+        let image = match self.image() {
+            Some(image) => image,
+            None => return,
+        };
+        self.image = None;
+        self.tiles.clear();
+
+        // User images can be larger than the maximum texture size, the font atlas is sized by
+        // egui to fit. Only the tiles of such images are kept.
+        let max_texture_side = self.max_texture_side as i32;
+        if self.paint_type == PaintType::Image
+            && (image.width() > max_texture_side || image.height() > max_texture_side)
+        {
+            if let Some(tiles) = split_into_tiles(&image, max_texture_side, self.sampling_options) {
+                self.tiles = tiles;
+                self.paint = image_paint(None);
+                return;
+            }
+        }
+
+        let local_matrix =
+            skia_safe::Matrix::scale((1.0 / image.width() as f32, 1.0 / image.height() as f32));
+        let tile_mode = self.wrap_mode.into();
+
+        let shader = image
+            .to_shader((tile_mode, tile_mode), self.sampling_options, &local_matrix)
+            .map(|shader| colorize_alpha_only(&image, shader));
+        self.paint = image_paint(shader);
+
+        self.white_uv_paint = (self.paint_type == PaintType::Font).then(|| {
+            image_paint(white_outside_shader(
+                &image,
+                self.sampling_options,
+                &local_matrix,
+            ))
+        });
+        self.image = Some(image);
+    }
+
+    /// The whole image, put back together from the tiles if it was split.
+    fn image(&self) -> Option<Image> {
+        match &self.image {
+            Some(image) => Some(image.clone()),
+            None => assemble_tiles(&self.tiles),
+        }
+    }
+}
+
+/// How texture coordinates outside of the `0..1` range are handled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TextureWrapMode {
+    /// Stretch the edge pixels of the texture.
+    #[default]
+    ClampToEdge,
+    /// Tile the texture.
+    Repeat,
+    /// Tile the texture, mirroring every other tile.
+    MirroredRepeat,
+}
+
+impl From<TextureWrapMode> for skia_safe::TileMode {
+    fn from(wrap_mode: TextureWrapMode) -> Self {
+        match wrap_mode {
+            TextureWrapMode::ClampToEdge => skia_safe::TileMode::Clamp,
+            TextureWrapMode::Repeat => skia_safe::TileMode::Repeat,
+            TextureWrapMode::MirroredRepeat => skia_safe::TileMode::Mirror,
+        }
+    }
+}
+
+// Egui use the uv coordinates 0,0 to get a white color when drawing vector graphics
+// 0,0 is always a white dot on the font texture
+// Unfortunately skia has a bug where it cannot get a color when the uv coordinates are equal
+// https://bugs.chromium.org/p/skia/issues/detail?id=13706
+// As a workaround, on raster canvases the uv coordinates of these vertices are replaced by their
+// position, moved far outside of the texture. The white_uv_paint draws everything outside of the
+// texture white, so the triangles keep their solid color but are no longer degenerate.
+const WHITE_UV_OFFSET: f32 = 65536.0;
+
+/// Whether to apply the workaround for skia's raster backend, which cannot sample a color when
+/// all uv coordinates of a triangle are equal.
+///
+/// See <https://github.com/lucasmerlin/egui_skia/issues/1> for more information.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CpuFix {
+    /// Apply the fix if the canvas is not backed by a gpu context.
+    Auto,
+    /// Always apply the fix.
+    Always,
+    /// Never apply the fix.
+    Never,
+}
+
+impl Default for CpuFix {
+    fn default() -> Self {
+        if cfg!(feature = "cpu_fix") {
+            CpuFix::Always
+        } else {
+            CpuFix::Auto
+        }
+    }
+}
+
+enum PaintBatch {
+    Mesh {
+        clip_rect: egui::Rect,
+        mesh: Mesh16,
+    },
+    Callback {
+        clip_rect: egui::Rect,
+        callback: PaintCallback,
+    },
+}
+
+/// How the font atlas is stored.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FontAtlasFormat {
+    /// Expand the coverage to premultiplied white rgba pixels.
+    Rgba,
+    /// Store only the coverage as an alpha only image, using a quarter of the memory and upload
+    /// bandwidth. The image is drawn white and modulated by the vertex colors, which renders the
+    /// same as [`Self::Rgba`].
+    #[default]
+    Alpha,
+}
+
+/// Options for [`Painter`].
+#[derive(Clone, Debug)]
+pub struct PainterOptions {
+    /// Gamma applied to the glyph coverage of the font atlas.
+    ///
+    /// Values below 1.0 make text heavier, values above 1.0 lighter. Defaults to 1.0.
+    pub font_gamma: f32,
+    /// Factor the glyph coverage is multiplied with after applying [`Self::font_gamma`].
+    ///
+    /// Values above 1.0 increase the contrast of text, which can help light text on dark
+    /// backgrounds. Defaults to 1.0.
+    pub font_contrast_boost: f32,
+    /// How the font atlas is stored, see [`FontAtlasFormat`].
+    pub font_atlas_format: FontAtlasFormat,
+    /// The maximum texture size reported to egui when painting to raster canvases. On gpu
+    /// canvases the limit of the gpu context is used. Larger user textures are split into
+    /// tiles. Defaults to 8192.
+    pub raster_max_texture_side: usize,
+    /// Draws the shadows of windows and popups with a skia blur instead of egui's feathered
+    /// meshes, which look smoother, especially on raster canvases. Only used by
+    /// [`crate::EguiSkia`], which recognizes the shadows before tessellating. Defaults to `false`.
+    pub native_shadows: bool,
+}
+
+impl Default for PainterOptions {
+    fn default() -> Self {
+        Self {
+            font_gamma: 1.0,
+            font_contrast_boost: 1.0,
+            font_atlas_format: FontAtlasFormat::default(),
+            raster_max_texture_side: 8192,
+            native_shadows: false,
+        }
+    }
+}
+
+/// Statistics about the last frame painted by [`Painter::paint_and_update_textures`].
+#[derive(Clone, Debug, Default)]
+pub struct PaintStats {
+    /// Number of u16 meshes egui produced, before batching.
+    pub meshes: usize,
+    /// Number of `draw_vertices` calls submitted to the canvas.
+    pub draw_calls: usize,
+    /// Number of paint callbacks drawn.
+    pub callbacks: usize,
+    /// Number of shadows drawn with a skia blur, see [`PainterOptions::native_shadows`].
+    pub native_shadows: usize,
+    /// Number of draw calls whose vertices were taken from the vertex cache.
+    pub cached_vertices: usize,
+    /// Number of entries in the vertex cache at the end of the frame.
+    pub vertex_cache_entries: usize,
 }
 
 pub struct Painter {
-    paints: AHashMap<TextureId, PaintHandle>,
-    white_paint_workaround: Paint,
+    textures: TextureStore,
+    stats: PaintStats,
+    batches: Vec<PaintBatch>,
+    vertex_cache: Option<VertexCache>,
+    cpu_fix: CpuFix,
+    options: PainterOptions,
+    texture_budget: Option<TextureBudget>,
+    max_texture_side: usize,
+    debug_overlay: DebugOverlay,
 }
 
 impl Painter {
     pub fn new() -> Painter {
-        let mut white_paint_workaround = Paint::default();
-        white_paint_workaround.set_color(Color::WHITE);
+        Self::new_with_options(PainterOptions::default())
+    }
 
+    /// Creates a painter with the given options.
+    ///
+    /// The font options are applied when egui uploads the font atlas, which happens on the first
+    /// frame and whenever the atlas has to be rebuilt.
+    pub fn new_with_options(options: PainterOptions) -> Painter {
+        Self::new_with_texture_store(options, TextureStore::new())
+    }
+
+    /// Creates a painter that uses the given texture store, which can be shared with other
+    /// painters, see [`TextureStore`].
+    pub fn new_with_texture_store(options: PainterOptions, textures: TextureStore) -> Painter {
         Self {
-            paints: AHashMap::new(),
-            white_paint_workaround,
+            textures,
+            stats: PaintStats::default(),
+            batches: Vec::new(),
+            vertex_cache: None,
+            cpu_fix: CpuFix::default(),
+            texture_budget: None,
+            max_texture_side: options.raster_max_texture_side,
+            options,
+            debug_overlay: DebugOverlay::default(),
+        }
+    }
+
+    /// The maximum texture side of the canvas painted to last, or
+    /// [`PainterOptions::raster_max_texture_side`] before the first frame.
+    ///
+    /// [`crate::EguiSkia::run`] passes this to egui, unless the host set
+    /// [`egui::RawInput::max_texture_side`] itself.
+    pub fn max_texture_side(&self) -> usize {
+        self.max_texture_side
+    }
+
+    /// Returns the store holding the textures of this painter. Pass a clone of it to
+    /// [`Self::new_with_texture_store`] to share the textures with another painter.
+    pub fn texture_store(&self) -> &TextureStore {
+        &self.textures
+    }
+
+    /// Returns the size and usage of all uploaded textures, least recently used first.
+    pub fn texture_usage(&self) -> Vec<TextureUsage> {
+        let mut textures = self
+            .textures
+            .lock()
+            .paints
+            .iter()
+            .map(|(id, handle)| TextureUsage {
+                id: *id,
+                bytes: handle.bytes(),
+                last_used_frame: handle.last_used_frame,
+            })
+            .collect::<Vec<_>>();
+        textures.sort_by_key(|texture| texture.last_used_frame);
+        textures
+    }
+
+    /// Returns the approximate number of bytes used by all uploaded textures.
+    pub fn texture_bytes(&self) -> usize {
+        self.textures
+            .lock()
+            .paints
+            .values()
+            .map(PaintHandle::bytes)
+            .sum()
+    }
+
+    /// Sets a budget in bytes for the uploaded textures.
+    ///
+    /// After each frame in which the textures use more memory than the budget, `on_exceeded` is
+    /// called and can evict textures, see [`TextureBudgetExceeded`].
+    pub fn set_texture_budget(
+        &mut self,
+        bytes: usize,
+        on_exceeded: impl FnMut(&mut TextureBudgetExceeded) + Send + 'static,
+    ) {
+        self.texture_budget = Some(TextureBudget {
+            bytes,
+            on_exceeded: Box::new(on_exceeded),
+        });
+    }
+
+    pub fn clear_texture_budget(&mut self) {
+        self.texture_budget = None;
+    }
+
+    pub fn options(&self) -> &PainterOptions {
+        &self.options
+    }
+
+    /// Sets how the texture is repeated when it is sampled outside of its bounds, e.g. for
+    /// tiled backgrounds drawn with uv coordinates larger than `1.0`.
+    ///
+    /// egui's [`egui::TextureOptions`] don't carry a wrap mode in the supported egui versions,
+    /// so it is set on the painter instead. The mode can be set before the texture is uploaded
+    /// and is kept across re-uploads, changing it updates the texture's shader right away.
+    pub fn set_texture_wrap_mode(&mut self, id: TextureId, wrap_mode: TextureWrapMode) {
+        let mut textures = self.textures.lock();
+        textures.wrap_modes.insert(id, wrap_mode);
+        if let Some(handle) = textures.paints.get_mut(&id) {
+            if handle.wrap_mode != wrap_mode {
+                handle.wrap_mode = wrap_mode;
+                // Tiled images apply the wrap mode when their meshes are split
+                if handle.tiles.is_empty() {
+                    handle.update_paints();
+                }
+            }
         }
     }
 
+    /// Sets when the raster workaround is applied, see [`CpuFix`].
+    ///
+    /// Defaults to [`CpuFix::Auto`], or [`CpuFix::Always`] if the `cpu_fix` feature is enabled.
+    /// Can be changed between calls to [`Self::paint_and_update_textures`], e.g. when the same
+    /// painter draws to both raster and gpu surfaces.
+    pub fn set_cpu_fix(&mut self, cpu_fix: CpuFix) {
+        self.cpu_fix = cpu_fix;
+    }
+
+    /// Enables or disables caching of converted vertex data between frames.
+    ///
+    /// When enabled, meshes that are identical to one painted in a previous frame reuse the
+    /// previously built [`Vertices`]. Entries that haven't been used for `max_unused_frames`
+    /// frames are evicted. Pass `None` to disable the cache and drop all cached entries.
+    pub fn set_vertex_cache(&mut self, max_unused_frames: Option<u64>) {
+        self.vertex_cache = max_unused_frames.map(VertexCache::new);
+    }
+
+    /// Draws a debug overlay on top of the painted frames, showing the meshes, clip rects,
+    /// textures and callbacks submitted to the canvas. Disabled by default, can be toggled
+    /// between frames. Use [`crate::EguiSkia::set_debug_overlay`] with damage tracking, so the
+    /// whole canvas is repainted when the overlay changes.
+    pub fn set_debug_overlay(&mut self, debug_overlay: DebugOverlay) {
+        self.debug_overlay = debug_overlay;
+    }
+
+    pub fn debug_overlay(&self) -> DebugOverlay {
+        self.debug_overlay
+    }
+
+    /// Returns statistics about the last painted frame.
+    pub fn stats(&self) -> &PaintStats {
+        &self.stats
+    }
+
+    /// Decodes an encoded image with skia's codecs (PNG, JPEG, WebP, GIF, BMP, ICO, ...) and adds
+    /// it to the textures of the painter.
+    ///
+    /// Returns the id of the texture, for use with e.g. [`egui::Ui::image`], and the size of the
+    /// image in pixels, or `None` if the image couldn't be decoded. Animated images show their
+    /// first frame. The texture stays until it's freed with [`Self::free_image`].
+    pub fn load_image(
+        &mut self,
+        encoded: &[u8],
+        options: egui::TextureOptions,
+    ) -> Option<(TextureId, egui::Vec2)> {
+        let image = Image::from_encoded(Data::new_copy(encoded))?;
+        // Decode now rather than on the first draw, like the textures uploaded by egui.
+        let image = image.to_raster_image(CachingHint::Allow)?;
+        let image = with_mipmaps_for(image, options);
+        let size = egui::vec2(image.width() as f32, image.height() as f32);
+
+        let mut textures = self.textures.lock();
+        let id = TextureId::User(textures.next_user_id);
+        textures.next_user_id += 1;
+        textures.paints.insert(
+            id,
+            PaintHandle::new(
+                image,
+                PaintType::Image,
+                sampling_options(options),
+                TextureWrapMode::default(),
+                self.max_texture_side,
+            ),
+        );
+
+        Some((id, size))
+    }
+
+    /// Removes an image added with [`Self::load_image`].
+    pub fn free_image(&mut self, id: TextureId) {
+        let mut textures = self.textures.lock();
+        textures.paints.remove(&id);
+        textures.wrap_modes.remove(&id);
+    }
+
+    /// Paints a frame returned by [`crate::EguiSkia::run_detached`].
+    ///
+    /// Clones of the frame can be painted by other painters sharing the [`TextureStore`] of this
+    /// one, the textures of the frame are only uploaded by the first of them.
+    pub fn paint_frame(&mut self, canvas: &mut Canvas, frame: FrameOutput) {
+        self.begin_frame(canvas, &frame.textures_delta, &frame.textures_uploaded);
+        self.paint_primitives(canvas, frame.pixels_per_point, frame.clipped_primitives);
+        self.end_frame();
+    }
+
     pub fn paint_and_update_textures(
         &mut self,
         canvas: &mut Canvas,
@@ -47,6 +488,35 @@ impl Painter {
         primitives: Vec<ClippedPrimitive>,
         textures_delta: TexturesDelta,
     ) {
+        self.begin_frame(canvas, &textures_delta, &AtomicBool::new(false));
+        self.paint_primitives(canvas, dpi, primitives);
+        self.end_frame();
+    }
+
+    /// Uploads the textures of `textures_delta` unless `textures_uploaded` is set, and starts a
+    /// frame, which can be painted with one or more calls to [`Self::paint_primitives`].
+    pub(crate) fn begin_frame(
+        &mut self,
+        canvas: &mut Canvas,
+        textures_delta: &TexturesDelta,
+        textures_uploaded: &AtomicBool,
+    ) {
+        self.update_max_texture_side(canvas);
+
+        let mut textures = self.textures.lock();
+        // Checked while holding the lock, so other painters wait for the upload
+        if !textures_uploaded.swap(true, Ordering::AcqRel) {
+            self.upload_textures(&mut textures, textures_delta);
+        }
+        drop(textures);
+
+        self.stats = PaintStats::default();
+    }
+
+    fn upload_textures(&self, textures: &mut Textures, textures_delta: &TexturesDelta) {
+        textures.free_pending();
+        textures.frame += 1;
+
         textures_delta.set.iter().for_each(|(id, image_delta)| {
             let delta_image = match &image_delta.image {
                 ImageData::Color(color_image) => Image::from_raster_data(
@@ -69,34 +539,50 @@ impl Painter {
                 )
                 .unwrap(),
                 ImageData::Font(font) => {
-                    let pixels = font.srgba_pixels(Some(1.0));
-                    Image::from_raster_data(
-                        &ImageInfo::new_n32_premul(
-                            skia_safe::ISize::new(font.width() as i32, font.height() as i32),
-                            None,
-                        ),
-                        Data::new_copy(
-                            pixels
-                                .flat_map(|p| p.to_array())
-                                .collect::<Vec<_>>()
-                                .as_slice(),
-                        ),
-                        font.width() * 4,
-                    )
-                    .unwrap()
+                    let size = skia_safe::ISize::new(font.width() as i32, font.height() as i32);
+                    let coverage = font.pixels.iter().map(|coverage| {
+                        let alpha = (coverage.powf(self.options.font_gamma)
+                            * self.options.font_contrast_boost)
+                            .min(1.0);
+                        (alpha * 255.0 + 0.5) as u8
+                    });
+
+                    match self.options.font_atlas_format {
+                        FontAtlasFormat::Rgba => Image::from_raster_data(
+                            &ImageInfo::new_n32_premul(size, None),
+                            Data::new_copy(
+                                coverage
+                                    .flat_map(|a| [a, a, a, a])
+                                    .collect::<Vec<_>>()
+                                    .as_slice(),
+                            ),
+                            font.width() * 4,
+                        )
+                        .unwrap(),
+                        FontAtlasFormat::Alpha => Image::from_raster_data(
+                            &ImageInfo::new_a8(size),
+                            Data::new_copy(coverage.collect::<Vec<_>>().as_slice()),
+                            font.width(),
+                        )
+                        .unwrap(),
+                    }
                 }
             };
 
             let image = match image_delta.pos {
                 None => delta_image,
                 Some(pos) => {
-                    let old_image = self.paints.remove(id).unwrap().image;
+                    let old_image = match textures.paints.remove(id) {
+                        Some(handle) => match handle.image() {
+                            Some(image) => image,
+                            None => return,
+                        },
+                        // Partial update of an evicted texture, wait for it to be uploaded again
+                        None => return,
+                    };
 
-                    let mut surface = Surface::new_raster_n32_premul(skia_safe::ISize::new(
-                        old_image.width() as i32,
-                        old_image.height() as i32,
-                    ))
-                    .unwrap();
+                    let mut surface =
+                        Surface::new_raster(&old_image.image_info(), None, None).unwrap();
 
                     let canvas = surface.canvas();
 
@@ -120,243 +606,502 @@ impl Painter {
                 }
             };
 
-            let local_matrix =
-                skia_safe::Matrix::scale((1.0 / image.width() as f32, 1.0 / image.height() as f32));
-
-            #[cfg(feature = "cpu_fix")]
-            let sampling_options = skia_safe::SamplingOptions::new(
-                skia_safe::FilterMode::Nearest,
-                skia_safe::MipmapMode::None,
-            );
-            #[cfg(not(feature = "cpu_fix"))]
-            let sampling_options = {
-                use egui::TextureFilter;
-                let filter_mode = match image_delta.options.magnification {
-                    TextureFilter::Nearest => skia_safe::FilterMode::Nearest,
-                    TextureFilter::Linear => skia_safe::FilterMode::Linear,
-                };
-                let mm_mode = match image_delta.options.minification {
-                    TextureFilter::Nearest => skia_safe::MipmapMode::Nearest,
-                    TextureFilter::Linear => skia_safe::MipmapMode::Linear,
-                };
-
-                skia_safe::SamplingOptions::new(filter_mode, mm_mode)
+            let image = if matches!(image_delta.image, ImageData::Color(_)) {
+                with_mipmaps_for(image, image_delta.options)
+            } else {
+                image
             };
-            let tile_mode = skia_safe::TileMode::Clamp;
-
-            let font_shader = image
-                .to_shader((tile_mode, tile_mode), sampling_options, &local_matrix)
-                .unwrap();
+            let sampling_options = sampling_options(image_delta.options);
 
-            image.width();
+            let paint_type = match image_delta.image {
+                ImageData::Color(_) => PaintType::Image,
+                ImageData::Font(_) => PaintType::Font,
+            };
 
-            let mut paint = Paint::default();
-            paint.set_shader(font_shader);
-            paint.set_color(Color::WHITE);
+            let wrap_mode = textures.wrap_modes.get(id).copied().unwrap_or_default();
 
-            self.paints.insert(
+            textures.paints.insert(
                 *id,
-                PaintHandle {
-                    paint,
+                PaintHandle::new(
                     image,
-                    paint_type: match image_delta.image {
-                        ImageData::Color(_) => PaintType::Image,
-                        ImageData::Font(_) => PaintType::Font,
-                    },
-                },
+                    paint_type,
+                    sampling_options,
+                    wrap_mode,
+                    self.max_texture_side,
+                ),
             );
         });
 
-        for primitive in primitives {
-            let skclip_rect = Rect::new(
-                primitive.clip_rect.min.x,
-                primitive.clip_rect.min.y,
-                primitive.clip_rect.max.x,
-                primitive.clip_rect.max.y,
-            );
-            match primitive.primitive {
-                Primitive::Mesh(mesh) => {
+        // Freed once all painters are done with this frame, see [`Textures::pending_free`]
+        textures.pending_free.extend(&textures_delta.free);
+    }
+
+    pub(crate) fn paint_primitives(
+        &mut self,
+        canvas: &mut Canvas,
+        dpi: f32,
+        primitives: Vec<ClippedPrimitive>,
+    ) {
+        let cpu_fix = match self.cpu_fix {
+            CpuFix::Auto => is_raster_canvas(canvas),
+            CpuFix::Always => true,
+            CpuFix::Never => false,
+        };
+
+        // The batch buffer is kept around between frames so its allocation can be reused.
+        let mut batches = std::mem::take(&mut self.batches);
+        self.batch_primitives(primitives, &mut batches);
+
+        let mut textures = self.textures.lock();
+        let frame = textures.frame;
+
+        let vertices = build_vertices(
+            &batches,
+            &textures,
+            cpu_fix,
+            self.vertex_cache.as_mut(),
+            &mut self.stats,
+        );
+
+        // Layers started by an [`ImageFilterEffect`] and not ended yet
+        let mut open_layers = 0;
+
+        let mut debug_shapes = self.debug_overlay.is_enabled().then(DebugShapes::default);
+
+        for (batch, vertices) in batches.drain(..).zip(vertices) {
+            match batch {
+                PaintBatch::Mesh { clip_rect, mesh } => {
+                    let skclip_rect = egui_rect_to_skia(clip_rect);
                     canvas.set_matrix(skia_safe::M44::new_identity().set_scale(dpi, dpi, 1.0));
                     let mut arc = skia_safe::AutoCanvasRestore::guard(canvas, true);
 
-                    #[cfg(feature = "cpu_fix")]
-                    let meshes = mesh
-                        .split_to_u16()
-                        .into_iter()
-                        .flat_map(|mesh| self.split_texture_meshes(mesh))
-                        .collect::<Vec<Mesh16>>();
-                    #[cfg(not(feature = "cpu_fix"))]
-                    let meshes = mesh.split_to_u16();
-
-                    for mesh in &meshes {
-                        let texture_id = mesh.texture_id;
-
-                        let mut pos = Vec::with_capacity(mesh.vertices.len());
-                        let mut texs = Vec::with_capacity(mesh.vertices.len());
-                        let mut colors = Vec::with_capacity(mesh.vertices.len());
-
-                        mesh.vertices.iter().enumerate().for_each(|(_i, v)| {
-                            // Apparently vertices can be NaN and if they are NaN, nothing is rendered.
-                            // Replacing them with 0 works around this.
-                            // https://github.com/lucasmerlin/egui_skia/issues/4
-                            let fixed_pos = if v.pos.x.is_nan() || v.pos.y.is_nan() {
-                                Pos2::new(0.0, 0.0)
-                            } else {
-                                v.pos
-                            };
-
-                            pos.push(Point::new(fixed_pos.x, fixed_pos.y));
-                            texs.push(Point::new(v.uv.x, v.uv.y));
-
-                            let c = v.color;
-                            let c = Color::from_argb(c.a(), c.r(), c.g(), c.b());
-                            // Un-premultply color
-                            // This fixes some cases of the color-test
-                            // https://github.com/lucasmerlin/egui_skia/issues/6
-                            // there might be a better solution though?
-                            let mut cf = skia_safe::Color4f::from(c);
-                            cf.r /= cf.a;
-                            cf.g /= cf.a;
-                            cf.b /= cf.a;
-                            colors.push(Color::from_argb(
-                                c.a(),
-                                (cf.r * 255.0) as u8,
-                                (cf.g * 255.0) as u8,
-                                (cf.b * 255.0) as u8,
-                            ));
-                        });
-
-                        // TODO: Use vertex builder
-                        // let mut vertex_builder = Builder::new(
-                        //     VertexMode::Triangles,
-                        //     mesh.vertices.len(),
-                        //     mesh.indices.len(),
-                        //     BuilderFlags::HAS_COLORS | BuilderFlags::HAS_TEX_COORDS,
-                        // );
-                        //
-                        // for (i, v) in mesh.vertices.iter().enumerate() {
-                        //     vertex_builder.positions()[i] = Point::new(v.pos.x, v.pos.y);
-                        //     vertex_builder.tex_coords().unwrap()[i] = Point::new(v.uv.x, v.uv.y);
-                        //     vertex_builder.colors().unwrap()[i] = Color::from_argb(
-                        //         v.color.a(),
-                        //         v.color.r(),
-                        //         v.color.g(),
-                        //         v.color.b(),
-                        //     );
-                        // }
-                        // let vertices = vertex_builder.detach();
-
-                        let vertices = Vertices::new_copy(
-                            VertexMode::Triangles,
-                            &pos,
-                            &texs,
-                            &colors,
-                            Some(
-                                mesh.indices
-                                    .iter()
-                                    .map(|index| *index as u16)
-                                    .collect::<Vec<u16>>()
-                                    .as_slice(),
-                            ),
-                        );
+                    // The texture might have been evicted, see [`Self::set_texture_budget`]
+                    let paint_handle = match textures.paints.get_mut(&mesh.texture_id) {
+                        Some(paint_handle) => paint_handle,
+                        None => continue,
+                    };
+                    paint_handle.last_used_frame = frame;
+                    let paint_handle = &*paint_handle;
+                    let remap_white_uv = cpu_fix && paint_handle.paint_type == PaintType::Font;
 
-                        arc.clip_rect(skclip_rect, ClipOp::default(), true);
-
-                        // Egui use the uv coordinates 0,0 to get a white color when drawing vector graphics
-                        // 0,0 is always a white dot on the font texture
-                        // Unfortunately skia has a bug where it cannot get a color when the uv coordinates are equal
-                        // https://bugs.chromium.org/p/skia/issues/detail?id=13706
-                        // As a workaround, split_texture_meshes splits meshes that contain both 0,0 vertices, as
-                        // well as non-0,0 vertices into multiple meshes.
-                        // Here we check if the mesh is a font texture and if it's first uv has 0,0
-                        // If yes, we use a white paint instead of the texture shader paint
-
-                        let cpu_fix = if cfg!(feature = "cpu_fix")
-                            && self.paints.get(&mesh.texture_id).unwrap().paint_type
-                                == PaintType::Font
-                        {
-                            !texs
-                                .first()
-                                .map(|point| point.x != 0.0 || point.y != 0.0)
-                                .unwrap()
-                        } else {
-                            false
-                        };
+                    arc.clip_rect(skclip_rect, ClipOp::default(), true);
 
-                        let paint = if cpu_fix {
-                            &self.white_paint_workaround
+                    if paint_handle.tiles.is_empty() {
+                        let paint = if remap_white_uv {
+                            paint_handle.white_uv_paint.as_ref().unwrap()
                         } else {
-                            &self.paints[&texture_id].paint
+                            &paint_handle.paint
                         };
 
-                        arc.draw_vertices(&vertices, BlendMode::Modulate, paint);
+                        arc.draw_vertices(&vertices.unwrap(), BlendMode::Modulate, paint);
+                        self.stats.draw_calls += 1;
+                    } else {
+                        let tile_meshes =
+                            split_mesh(&mesh, &paint_handle.tiles, paint_handle.wrap_mode);
+                        for (tile, tile_mesh) in paint_handle.tiles.iter().zip(tile_meshes) {
+                            if tile_mesh.is_empty() {
+                                continue;
+                            }
+                            for tile_mesh in tile_mesh.split_to_u16() {
+                                let vertices = mesh_to_vertices(&tile_mesh, false);
+                                arc.draw_vertices(&vertices, BlendMode::Modulate, &tile.paint);
+                                self.stats.draw_calls += 1;
+                            }
+                        }
+                    }
+
+                    if let Some(debug_shapes) = &mut debug_shapes {
+                        debug_shapes.add_mesh(
+                            &self.debug_overlay,
+                            &mesh,
+                            clip_rect,
+                            remap_white_uv,
+                        );
                     }
                 }
-                Primitive::Callback(data) => {
+                PaintBatch::Callback {
+                    clip_rect,
+                    callback: data,
+                } => {
+                    if let Some(debug_shapes) = &mut debug_shapes {
+                        debug_shapes.add_callback(&self.debug_overlay, data.rect, clip_rect);
+                    }
+
+                    if let Some(effect) = data.callback.downcast_ref::<ImageFilterEffect>() {
+                        canvas.set_matrix(skia_safe::M44::new_identity().set_scale(dpi, dpi, 1.0));
+                        effect.apply(canvas, clip_rect, data.rect, &mut open_layers);
+                        continue;
+                    }
+                    if let Some(shadow) = data.callback.downcast_ref::<NativeShadow>() {
+                        canvas.set_matrix(skia_safe::M44::new_identity().set_scale(dpi, dpi, 1.0));
+                        shadow.draw(canvas, clip_rect);
+                        self.stats.native_shadows += 1;
+                        continue;
+                    }
+
+                    let skclip_rect = egui_rect_to_skia(clip_rect);
                     let callback: Arc<EguiSkiaPaintCallback> = data.callback.downcast().unwrap();
                     let rect = data.rect;
 
-                    let skia_rect = Rect::new(
-                        rect.min.x * dpi,
-                        rect.min.y * dpi,
-                        rect.max.x * dpi,
-                        rect.max.y * dpi,
-                    );
+                    let skia_rect = Rect::from_wh(rect.width(), rect.height());
 
                     let mut drawable: Drawable = callback.callback.deref()(skia_rect).0.unwrap();
 
+                    canvas.set_matrix(skia_safe::M44::new_identity().set_scale(dpi, dpi, 1.0));
                     let mut arc = skia_safe::AutoCanvasRestore::guard(canvas, true);
 
                     arc.clip_rect(skclip_rect, ClipOp::default(), true);
                     arc.translate((rect.min.x, rect.min.y));
 
                     drawable.draw(&mut arc, None);
+                    self.stats.callbacks += 1;
                 }
             }
         }
 
-        textures_delta.free.iter().for_each(|id| {
-            self.paints.remove(id);
-        });
+        for _ in 0..open_layers {
+            canvas.restore();
+        }
+
+        if let Some(debug_shapes) = debug_shapes {
+            canvas.set_matrix(skia_safe::M44::new_identity().set_scale(dpi, dpi, 1.0));
+            debug_shapes.draw(canvas);
+        }
+
+        self.batches = batches;
     }
 
-    // This could be optimized more but works for now
-    #[cfg(feature = "cpu_fix")]
-    fn split_texture_meshes(&self, mesh: Mesh16) -> Vec<Mesh16> {
-        if self.paints.get(&mesh.texture_id).unwrap().paint_type != PaintType::Font {
-            return vec![mesh];
+    /// Ends the frame started by [`Self::begin_frame`].
+    pub(crate) fn end_frame(&mut self) {
+        if let Some(cache) = &mut self.vertex_cache {
+            cache.end_frame();
+            self.stats.vertex_cache_entries = cache.len();
         }
 
-        let mut is_zero = None;
+        self.enforce_texture_budget();
+    }
 
-        let mut meshes = Vec::new();
-        meshes.push(Mesh16 {
-            indices: vec![],
-            vertices: vec![],
-            texture_id: mesh.texture_id,
-        });
+    fn update_max_texture_side(&mut self, canvas: &mut Canvas) {
+        let max_texture_side =
+            gpu_max_texture_side(canvas).unwrap_or(self.options.raster_max_texture_side);
+
+        if max_texture_side != self.max_texture_side {
+            self.max_texture_side = max_texture_side;
+            // Re-tile the textures for a lower limit. Textures tiled for a lower limit also fit,
+            // so painters with different limits sharing a store don't re-tile every frame.
+            for handle in self.textures.lock().paints.values_mut() {
+                if handle.max_texture_side > max_texture_side {
+                    handle.max_texture_side = max_texture_side;
+                    handle.update_paints();
+                }
+            }
+        }
+    }
+
+    fn enforce_texture_budget(&mut self) {
+        let budget = match &self.texture_budget {
+            Some(budget) if self.texture_bytes() > budget.bytes => budget.bytes,
+            _ => return,
+        };
+
+        let textures = self.texture_usage();
+        let mut exceeded = TextureBudgetExceeded::new(budget, &textures);
+        (self.texture_budget.as_mut().unwrap().on_exceeded)(&mut exceeded);
+
+        let mut textures = self.textures.lock();
+        for id in exceeded.into_evicted() {
+            textures.paints.remove(&id);
+            textures.wrap_modes.remove(&id);
+        }
+    }
+
+    /// Splits the meshes into u16 meshes and merges consecutive ones that share a texture and
+    /// clip rect into batches, so that they can be submitted with a single `draw_vertices` call.
+    fn batch_primitives(
+        &mut self,
+        primitives: Vec<ClippedPrimitive>,
+        batches: &mut Vec<PaintBatch>,
+    ) {
+        let mut meshes = 0;
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            match primitive {
+                Primitive::Mesh(mesh) => {
+                    for mesh in mesh.split_to_u16() {
+                        meshes += 1;
 
-        for index in mesh.indices.iter() {
-            let vertex = mesh.vertices.get(*index as usize).unwrap();
-            let is_current_zero = vertex.uv.x == 0.0 && vertex.uv.y == 0.0;
-            if is_current_zero != is_zero.unwrap_or(is_current_zero) {
-                meshes.push(Mesh16 {
-                    indices: vec![],
-                    vertices: vec![],
-                    texture_id: mesh.texture_id,
-                });
-                is_zero = Some(is_current_zero)
+                        match batches.last_mut() {
+                            Some(PaintBatch::Mesh {
+                                clip_rect: batch_clip_rect,
+                                mesh: batch_mesh,
+                            }) if *batch_clip_rect == clip_rect
+                                && batch_mesh.texture_id == mesh.texture_id
+                                && batch_mesh.vertices.len() + mesh.vertices.len()
+                                    <= u16::MAX as usize + 1 =>
+                            {
+                                let index_offset = batch_mesh.vertices.len() as u16;
+                                batch_mesh
+                                    .indices
+                                    .extend(mesh.indices.iter().map(|index| index + index_offset));
+                                batch_mesh.vertices.extend(mesh.vertices);
+                            }
+                            _ => batches.push(PaintBatch::Mesh { clip_rect, mesh }),
+                        }
+                    }
+                }
+                Primitive::Callback(callback) => {
+                    batches.push(PaintBatch::Callback {
+                        clip_rect,
+                        callback,
+                    });
+                }
             }
-            if is_zero.is_none() {
-                is_zero = Some(is_current_zero)
+        }
+
+        self.stats.meshes += meshes;
+    }
+}
+
+/// Skia can only use the mipmap mode of the sampling options if the image has mipmaps, so
+/// generate them for images egui wants to be minified smoothly.
+fn with_mipmaps_for(image: Image, options: egui::TextureOptions) -> Image {
+    if options.minification == egui::TextureFilter::Linear {
+        image.with_default_mipmaps().unwrap_or(image)
+    } else {
+        image
+    }
+}
+
+fn sampling_options(options: egui::TextureOptions) -> skia_safe::SamplingOptions {
+    use egui::TextureFilter;
+    let filter_mode = match options.magnification {
+        TextureFilter::Nearest => skia_safe::FilterMode::Nearest,
+        TextureFilter::Linear => skia_safe::FilterMode::Linear,
+    };
+    let mm_mode = match options.minification {
+        TextureFilter::Nearest => skia_safe::MipmapMode::None,
+        TextureFilter::Linear => skia_safe::MipmapMode::Linear,
+    };
+
+    skia_safe::SamplingOptions::new(filter_mode, mm_mode)
+}
+
+/// Builds the vertices of the mesh batches, indexed like `batches`. Callbacks and meshes using a
+/// missing texture get `None`.
+///
+/// Vertices that aren't in the vertex cache are built on all cores with the `rayon` feature.
+fn build_vertices(
+    batches: &[PaintBatch],
+    textures: &Textures,
+    cpu_fix: bool,
+    mut vertex_cache: Option<&mut VertexCache>,
+    stats: &mut PaintStats,
+) -> Vec<Option<Vertices>> {
+    let mut vertices = vec![None; batches.len()];
+    let mut missing = Vec::new();
+
+    for (index, batch) in batches.iter().enumerate() {
+        let mesh = match batch {
+            PaintBatch::Mesh { mesh, .. } => mesh,
+            PaintBatch::Callback { .. } => continue,
+        };
+        let remap_white_uv = match textures.paints.get(&mesh.texture_id) {
+            // Meshes using tiled images are split per tile when they are drawn
+            Some(paint_handle) if !paint_handle.tiles.is_empty() => continue,
+            Some(paint_handle) => cpu_fix && paint_handle.paint_type == PaintType::Font,
+            None => continue,
+        };
+        let key = vertex_cache
+            .is_some()
+            .then(|| VertexCache::key(mesh, remap_white_uv));
+        match key.and_then(|key| vertex_cache.as_mut()?.get(key)) {
+            Some(cached) => {
+                vertices[index] = Some(cached);
+                stats.cached_vertices += 1;
             }
-            let last = meshes.last_mut().unwrap();
-            last.vertices.push(vertex.clone());
-            last.indices.push(last.indices.len() as u16);
+            None => missing.push((index, key, mesh, remap_white_uv)),
         }
+    }
+
+    #[cfg(feature = "rayon")]
+    let built: Vec<Vertices> = missing
+        .par_iter()
+        .map(|(_, _, mesh, remap_white_uv)| mesh_to_vertices(mesh, *remap_white_uv))
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let built: Vec<Vertices> = missing
+        .iter()
+        .map(|(_, _, mesh, remap_white_uv)| mesh_to_vertices(mesh, *remap_white_uv))
+        .collect();
+
+    for ((index, key, _, _), built) in missing.into_iter().zip(built) {
+        if let (Some(cache), Some(key)) = (vertex_cache.as_mut(), key) {
+            cache.insert(key, built.clone());
+        }
+        vertices[index] = Some(built);
+    }
+
+    vertices
+}
+
+/// Converts an egui mesh into skia vertices, writing directly into the buffers of a
+/// [`vertices::Builder`] so no intermediate vectors have to be allocated.
+///
+/// With `remap_white_uv`, the uv coordinates of vertices using [`egui::epaint::WHITE_UV`] are
+/// replaced, see [`WHITE_UV_OFFSET`].
+fn mesh_to_vertices(mesh: &Mesh16, remap_white_uv: bool) -> Vertices {
+    let mut builder = vertices::Builder::new(
+        VertexMode::Triangles,
+        mesh.vertices.len(),
+        mesh.indices.len(),
+        BuilderFlags::HAS_COLORS | BuilderFlags::HAS_TEX_COORDS,
+    );
+
+    for (point, v) in builder.positions().iter_mut().zip(&mesh.vertices) {
+        // Apparently vertices can be NaN and if they are NaN, nothing is rendered.
+        // Replacing them with 0 works around this.
+        // https://github.com/lucasmerlin/egui_skia/issues/4
+        *point = if v.pos.x.is_nan() || v.pos.y.is_nan() {
+            Point::new(0.0, 0.0)
+        } else {
+            Point::new(v.pos.x, v.pos.y)
+        };
+    }
+
+    for (point, v) in builder.tex_coords().unwrap().iter_mut().zip(&mesh.vertices) {
+        *point = if remap_white_uv && v.uv == WHITE_UV {
+            Point::new(v.pos.x - WHITE_UV_OFFSET, v.pos.y)
+        } else {
+            Point::new(v.uv.x, v.uv.y)
+        };
+    }
 
-        meshes
+    for (color, v) in builder.colors().unwrap().iter_mut().zip(&mesh.vertices) {
+        *color = unpremultiply(v.color);
     }
+
+    builder
+        .indices()
+        .unwrap()
+        .copy_from_slice(mesh.indices.as_slice());
+
+    builder.detach()
+}
+
+// Un-premultply color
+// This fixes some cases of the color-test
+// https://github.com/lucasmerlin/egui_skia/issues/6
+// there might be a better solution though?
+pub(crate) fn unpremultiply(color: Color32) -> Color {
+    let c = Color::from_argb(color.a(), color.r(), color.g(), color.b());
+    let mut cf = skia_safe::Color4f::from(c);
+    cf.r /= cf.a;
+    cf.g /= cf.a;
+    cf.b /= cf.a;
+    Color::from_argb(
+        c.a(),
+        (cf.r * 255.0) as u8,
+        (cf.g * 255.0) as u8,
+        (cf.b * 255.0) as u8,
+    )
+}
+
+fn image_paint(shader: Option<Shader>) -> Paint {
+    let mut paint = Paint::default();
+    paint.set_shader(shader);
+    paint.set_color(Color::WHITE);
+    paint
+}
+
+/// Alpha only images (like the font atlas stored as [`FontAtlasFormat::Alpha`]) are turned into
+/// white with the image's alpha, the same color the rgba font atlas has.
+fn colorize_alpha_only(image: &Image, shader: Shader) -> Shader {
+    if image.is_alpha_only() {
+        shaders::blend(BlendMode::DstIn, shaders::color(Color::WHITE), shader)
+    } else {
+        shader
+    }
+}
+
+/// A shader that samples the image inside of its bounds and is white everywhere outside of it.
+fn white_outside_shader(
+    image: &Image,
+    sampling_options: skia_safe::SamplingOptions,
+    local_matrix: &skia_safe::Matrix,
+) -> Option<Shader> {
+    let decal = (skia_safe::TileMode::Decal, skia_safe::TileMode::Decal);
+
+    let image_shader = colorize_alpha_only(
+        image,
+        image.to_shader(decal, sampling_options, local_matrix)?,
+    );
+
+    // Opaque inside of the image bounds, transparent outside
+    let inside_shader = white_pixel_shader(&skia_safe::Matrix::default())?;
+
+    let white_outside_shader = shaders::blend(
+        BlendMode::DstOut,
+        shaders::color(Color::WHITE),
+        inside_shader,
+    );
+
+    Some(shaders::blend(
+        BlendMode::SrcOver,
+        white_outside_shader,
+        image_shader,
+    ))
+}
+
+/// A shader that is opaque white inside of the unit square transformed by `local_matrix`, and
+/// transparent outside of it.
+fn white_pixel_shader(local_matrix: &skia_safe::Matrix) -> Option<Shader> {
+    let decal = (skia_safe::TileMode::Decal, skia_safe::TileMode::Decal);
+
+    Image::from_raster_data(
+        &ImageInfo::new_n32_premul(skia_safe::ISize::new(1, 1), None),
+        Data::new_copy(&[255, 255, 255, 255]),
+        4,
+    )?
+    .to_shader(
+        decal,
+        skia_safe::SamplingOptions::new(
+            skia_safe::FilterMode::Nearest,
+            skia_safe::MipmapMode::None,
+        ),
+        local_matrix,
+    )
+}
+
+/// The maximum texture size of the canvas' gpu context, `None` for raster canvases.
+#[cfg(any(feature = "gl", feature = "vulkan", feature = "metal"))]
+fn gpu_max_texture_side(canvas: &mut Canvas) -> Option<usize> {
+    canvas
+        .recording_context()
+        .map(|context| context.max_texture_size() as usize)
+}
+
+#[cfg(not(any(feature = "gl", feature = "vulkan", feature = "metal")))]
+fn gpu_max_texture_side(_canvas: &mut Canvas) -> Option<usize> {
+    None
+}
+
+/// Canvases without a gpu context are drawn by skia's raster backend.
+#[cfg(any(feature = "gl", feature = "vulkan", feature = "metal"))]
+fn is_raster_canvas(canvas: &mut Canvas) -> bool {
+    canvas.recording_context().is_none()
+}
+
+#[cfg(not(any(feature = "gl", feature = "vulkan", feature = "metal")))]
+fn is_raster_canvas(_canvas: &mut Canvas) -> bool {
+    true
+}
+
+pub(crate) fn egui_rect_to_skia(rect: egui::Rect) -> Rect {
+    Rect::new(rect.min.x, rect.min.y, rect.max.x, rect.max.y)
 }
 
 impl Default for Painter {
@@ -365,6 +1110,10 @@ impl Default for Painter {
     }
 }
 
+/// Draws to the canvas with skia, for use as the callback of an [`egui::PaintCallback`].
+///
+/// The callback draws in points, with the origin at the top left corner of the callback's rect.
+/// The canvas is scaled by `pixels_per_point`, so vector content stays crisp at any scale.
 pub struct EguiSkiaPaintCallback {
     callback: Box<dyn Fn(Rect) -> SyncSendableDrawable + Send + Sync>,
 }
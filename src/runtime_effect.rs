@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use egui::epaint::ahash::AHashMap;
+use egui::{PaintCallback, Rect, Response, Sense, Ui, Vec2};
+use skia_safe::{Data, Paint, PictureRecorder, RuntimeEffect};
+
+use crate::EguiSkiaPaintCallback;
+
+/// Fills a rect with an SkSL shader, compiled to a skia [`RuntimeEffect`]. Runtime effects run
+/// on the gpu and raster backends alike.
+///
+/// The shader's `main(float2 coord)` gets the position in points relative to the top left
+/// corner of the rect. These uniforms are set when the shader declares them:
+///
+/// * `uniform float time;` seconds since egui started. The ui is repainted continuously while a
+///   shader using it is visible.
+/// * `uniform float2 size;` the size of the rect in points.
+/// * `uniform float2 pointer;` the pointer position relative to the rect, or `(-1, -1)` if the
+///   pointer isn't over the rect.
+///
+/// Other uniforms are set with [`Self::set_uniform`].
+pub struct ShaderFill {
+    effect: RuntimeEffect,
+    uniforms: AHashMap<String, Vec<f32>>,
+}
+
+impl ShaderFill {
+    /// Compiles the shader, returning skia's error message if it doesn't compile.
+    pub fn new(sksl: &str) -> Result<Self, String> {
+        Ok(Self {
+            effect: RuntimeEffect::make_for_shader(sksl, None)?,
+            uniforms: AHashMap::new(),
+        })
+    }
+
+    /// Sets a float uniform (`float`, `float2`, ..., `float4x4` or arrays of them).
+    pub fn set_uniform(&mut self, name: &str, values: &[f32]) {
+        self.uniforms.insert(name.to_owned(), values.to_vec());
+    }
+
+    /// Allocates `size` and fills it with the shader.
+    pub fn show(&self, ui: &mut Ui, size: Vec2) -> Response {
+        let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+        self.paint(ui, rect);
+        response
+    }
+
+    /// Fills `rect` with the shader, e.g. as the background of a widget painted over it.
+    pub fn paint(&self, ui: &Ui, rect: Rect) {
+        if !ui.is_rect_visible(rect) {
+            return;
+        }
+
+        let pointer = ui
+            .input(|input| input.pointer.hover_pos())
+            .filter(|pos| rect.contains(*pos))
+            .map_or(Vec2::splat(-1.0), |pos| pos - rect.min);
+        let time = ui.input(|input| input.time) as f32;
+
+        let mut uniforms = self.uniforms.clone();
+        uniforms.insert("time".to_owned(), vec![time]);
+        uniforms.insert("size".to_owned(), vec![rect.width(), rect.height()]);
+        uniforms.insert("pointer".to_owned(), vec![pointer.x, pointer.y]);
+
+        if self
+            .effect
+            .uniforms()
+            .iter()
+            .any(|uniform| uniform.name() == "time")
+        {
+            ui.ctx().request_repaint();
+        }
+
+        let shader = match self
+            .effect
+            .make_shader(self.uniform_data(&uniforms), &[], None)
+        {
+            Some(shader) => shader,
+            None => return,
+        };
+
+        // Record the fill, so it can be sent to the painter
+        let mut paint = Paint::default();
+        paint.set_shader(shader);
+        let bounds = skia_safe::Rect::from_wh(rect.width(), rect.height());
+        let mut recorder = PictureRecorder::new();
+        recorder
+            .begin_recording(bounds, None)
+            .draw_rect(bounds, &paint);
+        let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+        ui.painter().add(PaintCallback {
+            rect,
+            callback: Arc::new(EguiSkiaPaintCallback::new(move |canvas| {
+                canvas.draw_picture(&picture, None, None);
+            })),
+        });
+    }
+
+    /// Lays out the uniforms the shader declares, leaving the ones without a value zeroed.
+    fn uniform_data(&self, values: &AHashMap<String, Vec<f32>>) -> Data {
+        let mut data = vec![0u8; self.effect.uniform_size()];
+
+        for uniform in self.effect.uniforms() {
+            if let Some(values) = values.get(uniform.name()) {
+                let bytes = values.iter().flat_map(|value| value.to_ne_bytes());
+                let start = uniform.offset();
+                let end = start + uniform.size_in_bytes();
+                for (target, byte) in data[start..end].iter_mut().zip(bytes) {
+                    *target = byte;
+                }
+            }
+        }
+
+        Data::new_copy(&data)
+    }
+}
@@ -0,0 +1,152 @@
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use egui::epaint::{ClippedShape, Mesh, Vertex, WHITE_UV};
+use egui::{Color32, PaintCallback, Pos2, Rect, Shape, TextureId};
+use skia_safe::{BlurStyle, Canvas, ClipOp, MaskFilter, Paint, RRect, Vector};
+
+use crate::painter::{egui_rect_to_skia, unpremultiply};
+
+/// A window or popup shadow drawn with a skia blur instead of egui's feathered mesh, see
+/// [`crate::PainterOptions::native_shadows`].
+pub(crate) struct NativeShadow {
+    /// The rect of the rounded rect egui feathers, half the extrusion larger than the window.
+    rect: Rect,
+    /// Corner radii in the order nw, ne, se, sw.
+    radii: [f32; 4],
+    color: Color32,
+    sigma: f32,
+}
+
+impl NativeShadow {
+    pub fn draw(&self, canvas: &mut Canvas, clip_rect: Rect) {
+        let radii = self.radii.map(|radius| Vector::new(radius, radius));
+        let rrect = RRect::new_rect_radii(egui_rect_to_skia(self.rect), &radii);
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(unpremultiply(self.color));
+        paint.set_mask_filter(MaskFilter::blur(BlurStyle::Normal, self.sigma, true));
+
+        let mut arc = skia_safe::AutoCanvasRestore::guard(canvas, true);
+        arc.clip_rect(egui_rect_to_skia(clip_rect), ClipOp::default(), true);
+        arc.draw_rrect(rrect, &paint);
+    }
+
+    /// Hashes the parameters of the shadow, for damage tracking.
+    pub fn hash(&self, state: &mut impl Hasher) {
+        for value in [
+            self.rect.min.x,
+            self.rect.min.y,
+            self.rect.max.x,
+            self.rect.max.y,
+        ] {
+            value.to_bits().hash(state);
+        }
+        for radius in self.radii {
+            radius.to_bits().hash(state);
+        }
+        self.color.hash(state);
+        self.sigma.to_bits().hash(state);
+    }
+}
+
+/// Replaces the shadow meshes created by [`egui::epaint::Shadow::tessellate`] with
+/// [`NativeShadow`]s.
+pub(crate) fn replace_shadows(shapes: &mut [ClippedShape]) {
+    for ClippedShape(_, shape) in shapes {
+        replace_shadow(shape);
+    }
+}
+
+/// egui's frames add their shadow and the frame itself as a [`Shape::Vec`], so nested shapes
+/// are searched as well. The tessellator turns callbacks nested in a [`Shape::Vec`] into
+/// primitives of their own.
+fn replace_shadow(shape: &mut Shape) {
+    match shape {
+        Shape::Vec(shapes) => shapes.iter_mut().for_each(replace_shadow),
+        Shape::Mesh(mesh) => {
+            if let Some(shadow) = recognize_shadow(mesh) {
+                // A gaussian extends about three sigma beyond the edge of the rounded rect
+                let rect = shadow.rect.expand(3.0 * shadow.sigma);
+                *shape = Shape::Callback(PaintCallback {
+                    rect,
+                    callback: Arc::new(shadow),
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Recognizes a shadow mesh by its structure.
+///
+/// egui tessellates a shadow as a rounded rect, half the extrusion larger than the window, that
+/// is feathered by the extrusion. The feathering emits pairs of an inner vertex with the shadow
+/// color and an outer transparent vertex, and the inner vertices outline the window's rect and
+/// rounding.
+fn recognize_shadow(mesh: &Mesh) -> Option<NativeShadow> {
+    if mesh.texture_id != TextureId::default()
+        || mesh.vertices.len() < 8
+        || mesh.vertices.len() % 2 != 0
+        || mesh.vertices.iter().any(|vertex| vertex.uv != WHITE_UV)
+    {
+        return None;
+    }
+
+    let color = mesh.vertices[0].color;
+    if color == Color32::TRANSPARENT {
+        return None;
+    }
+
+    let pairs = mesh.vertices.chunks_exact(2);
+    let is_feathered = pairs
+        .clone()
+        .all(|pair| pair[0].color == color && pair[1].color == Color32::TRANSPARENT);
+    if !is_feathered {
+        return None;
+    }
+
+    // The vertices of the straight edges are the feathering width apart, the ones around the
+    // corners slightly more as the normals of the polygon are lengthened there.
+    let extrusion = pairs
+        .clone()
+        .map(|pair| pair[0].pos.distance(pair[1].pos))
+        .fold(f32::INFINITY, f32::min);
+    let is_uniform = pairs
+        .clone()
+        .all(|pair| pair[0].pos.distance(pair[1].pos) <= extrusion * 1.1);
+    if !is_uniform || extrusion <= 0.0 {
+        return None;
+    }
+
+    let inner: Vec<&Vertex> = pairs.map(|pair| &pair[0]).collect();
+    let window_rect = Rect::from_points(&inner.iter().map(|vertex| vertex.pos).collect::<Vec<_>>());
+    let half_extrusion = extrusion / 2.0;
+
+    let corners = [
+        window_rect.left_top(),
+        window_rect.right_top(),
+        window_rect.right_bottom(),
+        window_rect.left_bottom(),
+    ];
+    let radii = corners.map(|corner| corner_radius(&inner, corner) + half_extrusion);
+
+    Some(NativeShadow {
+        rect: window_rect.expand(half_extrusion),
+        radii,
+        color,
+        // A gaussian with the standard deviation of the linear feathering ramp
+        sigma: extrusion / 12f32.sqrt(),
+    })
+}
+
+/// Estimates the radius of a rounded corner from the distance of the outline to the corner of
+/// its bounding rect, which is `radius * (√2 - 1)` for a circular corner.
+fn corner_radius(outline: &[&Vertex], corner: Pos2) -> f32 {
+    let distance = outline
+        .iter()
+        .map(|vertex| vertex.pos.distance(corner))
+        .fold(f32::INFINITY, f32::min);
+    distance / (std::f32::consts::SQRT_2 - 1.0)
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use egui::{PaintCallback, Response, Sense, Ui, Vec2};
+use skia_safe::svg::{Dom, LoadError};
+use skia_safe::{Picture, PictureRecorder, Rect, Size};
+
+use crate::EguiSkiaPaintCallback;
+
+/// An svg document, parsed and recorded with skia's svg module and drawn as vector content.
+///
+/// Unlike an svg rasterized to an egui texture, it stays crisp at any size and
+/// `pixels_per_point`. The methods are named like the ones of `egui_extras::RetainedImage`.
+#[derive(Clone)]
+pub struct SvgImage {
+    picture: Picture,
+    size: Vec2,
+}
+
+impl SvgImage {
+    /// Parses the svg and lays it out for a size in points. Documents with a `viewBox` are
+    /// scaled to fit the size.
+    pub fn from_bytes(bytes: &[u8], size: Vec2) -> Result<Self, LoadError> {
+        let mut dom = Dom::from_bytes(bytes)?;
+        dom.set_container_size(Size::new(size.x, size.y));
+
+        let mut recorder = PictureRecorder::new();
+        let canvas = recorder.begin_recording(Rect::from_wh(size.x, size.y), None);
+        dom.render(canvas);
+
+        Ok(Self {
+            picture: recorder.finish_recording_as_picture(None).unwrap(),
+            size,
+        })
+    }
+
+    pub fn from_str(svg: &str, size: Vec2) -> Result<Self, LoadError> {
+        Self::from_bytes(svg.as_bytes(), size)
+    }
+
+    /// The size the svg was laid out for, in points.
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+
+    /// Shows the svg at the size it was laid out for.
+    pub fn show(&self, ui: &mut Ui) -> Response {
+        self.show_size(ui, self.size)
+    }
+
+    /// Shows the svg scaled to fit into `max_size`, keeping its aspect ratio.
+    pub fn show_max_size(&self, ui: &mut Ui, max_size: Vec2) -> Response {
+        let scale = (max_size / self.size).min_elem().min(1.0);
+        self.show_size(ui, self.size * scale)
+    }
+
+    pub fn show_scaled(&self, ui: &mut Ui, scale: f32) -> Response {
+        self.show_size(ui, self.size * scale)
+    }
+
+    /// Shows the svg stretched to `desired_size`.
+    pub fn show_size(&self, ui: &mut Ui, desired_size: Vec2) -> Response {
+        let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
+
+        if ui.is_rect_visible(rect) {
+            let picture = self.picture.clone();
+            let scale = rect.size() / self.size;
+            ui.painter().add(PaintCallback {
+                rect,
+                callback: Arc::new(EguiSkiaPaintCallback::new(move |canvas| {
+                    canvas.scale((scale.x, scale.y));
+                    canvas.draw_picture(&picture, None, None);
+                })),
+            });
+        }
+
+        response
+    }
+}
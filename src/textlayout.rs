@@ -0,0 +1,174 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use egui::{Color32, PaintCallback, Pos2, Response, Sense, Ui};
+use skia_safe::textlayout::{FontCollection, Paragraph, RectHeightStyle, RectWidthStyle};
+use skia_safe::{FontMgr, Picture, PictureRecorder, Rect};
+
+use crate::EguiSkiaPaintCallback;
+
+/// Returns a font collection that finds fonts and fallback fonts on the system.
+pub fn system_font_collection() -> FontCollection {
+    let mut font_collection = FontCollection::new();
+    font_collection.set_default_font_manager(FontMgr::new(), None);
+    font_collection
+}
+
+/// A label showing a paragraph laid out by skia's textlayout module.
+///
+/// Unlike egui's text, skia shapes complex scripts, handles bidirectional text, falls back to
+/// other fonts for missing glyphs and draws decorations. Build the paragraph with a
+/// [`skia_safe::textlayout::ParagraphBuilder`], the label lays it out for the available width.
+///
+/// The text can be selected by dragging over it. Text positions are in UTF-16 code units, like
+/// everywhere in skia's textlayout module.
+pub struct ParagraphLabel {
+    paragraph: Paragraph,
+    /// The paragraph recorded for the width it was last laid out for.
+    layout: Option<(f32, Picture)>,
+    selectable: bool,
+    selection: Option<Range<usize>>,
+    selection_anchor: usize,
+    selection_color: Option<Color32>,
+}
+
+impl ParagraphLabel {
+    pub fn new(paragraph: Paragraph) -> Self {
+        Self {
+            paragraph,
+            layout: None,
+            selectable: true,
+            selection: None,
+            selection_anchor: 0,
+            selection_color: None,
+        }
+    }
+
+    /// Whether the text can be selected by dragging, `true` by default.
+    pub fn set_selectable(&mut self, selectable: bool) {
+        self.selectable = selectable;
+        if !selectable {
+            self.selection = None;
+        }
+    }
+
+    /// The color selected text is highlighted with, egui's selection color by default.
+    pub fn set_selection_color(&mut self, color: Color32) {
+        self.selection_color = Some(color);
+    }
+
+    /// The selected range of the text, in UTF-16 code units.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.selection.clone()
+    }
+
+    pub fn set_selection(&mut self, selection: Option<Range<usize>>) {
+        self.selection = selection;
+    }
+
+    pub fn paragraph(&self) -> &Paragraph {
+        &self.paragraph
+    }
+
+    /// Returns the position in the text closest to `pos`, relative to the top left corner of
+    /// the label.
+    pub fn text_position_at(&self, pos: Pos2) -> usize {
+        let position = self
+            .paragraph
+            .get_glyph_position_at_coordinate((pos.x, pos.y));
+        position.position.max(0) as usize
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) -> Response {
+        let width = ui.available_width();
+        let picture = self.layout(width);
+        let size = egui::vec2(self.paragraph.max_width(), self.paragraph.height().ceil());
+
+        let sense = if self.selectable {
+            Sense::click_and_drag()
+        } else {
+            Sense::hover()
+        };
+        let (rect, response) = ui.allocate_exact_size(size, sense);
+
+        if self.selectable {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                let position = self.text_position_at((pointer - rect.min).to_pos2());
+                if response.drag_started() || response.clicked() {
+                    self.selection_anchor = position;
+                }
+                let start = self.selection_anchor.min(position);
+                let end = self.selection_anchor.max(position);
+                self.selection = (start < end).then_some(start..end);
+            }
+            if response.hovered() {
+                ui.output_mut(|output| output.cursor_icon = egui::CursorIcon::Text);
+            }
+        }
+
+        if ui.is_rect_visible(rect) {
+            if let Some(selection) = &self.selection {
+                let color = self
+                    .selection_color
+                    .unwrap_or(ui.visuals().selection.bg_fill);
+                for text_box in self.paragraph.get_rects_for_range(
+                    selection.clone(),
+                    RectHeightStyle::Max,
+                    RectWidthStyle::Tight,
+                ) {
+                    let Rect {
+                        left,
+                        top,
+                        right,
+                        bottom,
+                    } = text_box.rect;
+                    ui.painter().rect_filled(
+                        egui::Rect::from_min_max(
+                            rect.min + egui::vec2(left, top),
+                            rect.min + egui::vec2(right, bottom),
+                        ),
+                        0.0,
+                        color,
+                    );
+                }
+            }
+
+            ui.painter().add(PaintCallback {
+                rect,
+                callback: Arc::new(EguiSkiaPaintCallback::new(move |canvas| {
+                    canvas.draw_picture(&picture, None, None);
+                })),
+            });
+        }
+
+        response
+    }
+
+    /// Lays the paragraph out for `width` if needed and returns it recorded as a picture, which
+    /// can be sent to the painter.
+    fn layout(&mut self, width: f32) -> Picture {
+        match &self.layout {
+            Some((laid_out_width, picture)) if *laid_out_width == width => picture.clone(),
+            _ => {
+                self.paragraph.layout(width);
+                // Shrink short text to its width, so the label doesn't allocate space next to
+                // right aligned or right-to-left text.
+                let intrinsic_width = self.paragraph.max_intrinsic_width().ceil();
+                if intrinsic_width < width {
+                    self.paragraph.layout(intrinsic_width);
+                }
+
+                let mut recorder = PictureRecorder::new();
+                let canvas = recorder.begin_recording(
+                    Rect::from_wh(self.paragraph.max_width(), self.paragraph.height()),
+                    None,
+                );
+                self.paragraph.paint(canvas, (0.0, 0.0));
+                let picture = recorder.finish_recording_as_picture(None).unwrap();
+
+                self.layout = Some((width, picture.clone()));
+                picture
+            }
+        }
+    }
+}
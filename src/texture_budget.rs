@@ -0,0 +1,85 @@
+use egui::TextureId;
+
+/// Size and usage of a texture uploaded to the [`crate::Painter`].
+#[derive(Clone, Debug)]
+pub struct TextureUsage {
+    pub id: TextureId,
+    /// Approximate size of the texture's pixels in bytes, including mipmaps.
+    pub bytes: usize,
+    /// The last frame the texture was drawn in, counted by the frames whose textures were
+    /// uploaded to the [`crate::TextureStore`].
+    pub last_used_frame: u64,
+}
+
+/// Passed to the callback set with [`crate::Painter::set_texture_budget`] when the uploaded
+/// textures use more memory than the budget.
+pub struct TextureBudgetExceeded<'a> {
+    budget: usize,
+    used: usize,
+    textures: &'a [TextureUsage],
+    evicted: Vec<TextureId>,
+}
+
+impl<'a> TextureBudgetExceeded<'a> {
+    pub(crate) fn new(budget: usize, textures: &'a [TextureUsage]) -> Self {
+        Self {
+            budget,
+            used: textures.iter().map(|texture| texture.bytes).sum(),
+            textures,
+            evicted: Vec::new(),
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Bytes used by the textures that are still resident.
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    /// All resident textures, least recently used first.
+    pub fn textures(&self) -> &[TextureUsage] {
+        self.textures
+    }
+
+    /// Removes the texture from the painter.
+    ///
+    /// Meshes using an evicted texture are skipped until egui uploads it again, so the host
+    /// should also free it on the egui side (e.g. by dropping the [`egui::TextureHandle`]) and
+    /// reload it when it is needed again. The font atlas cannot be evicted.
+    pub fn evict(&mut self, id: TextureId) {
+        if id == TextureId::default() || self.evicted.contains(&id) {
+            return;
+        }
+        if let Some(texture) = self.textures.iter().find(|texture| texture.id == id) {
+            self.used -= texture.bytes;
+            self.evicted.push(id);
+        }
+    }
+
+    /// Evicts the least recently used textures until the budget is met.
+    pub fn evict_least_recently_used(&mut self) {
+        for texture in self.textures {
+            if self.used <= self.budget {
+                break;
+            }
+            self.evict(texture.id);
+        }
+    }
+
+    /// The textures evicted by the callback.
+    pub fn evicted(&self) -> &[TextureId] {
+        &self.evicted
+    }
+
+    pub(crate) fn into_evicted(self) -> Vec<TextureId> {
+        self.evicted
+    }
+}
+
+pub(crate) struct TextureBudget {
+    pub bytes: usize,
+    pub on_exceeded: Box<dyn FnMut(&mut TextureBudgetExceeded) + Send>,
+}
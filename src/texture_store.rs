@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use egui::epaint::ahash::AHashMap;
+use egui::TextureId;
+
+use crate::painter::{PaintHandle, TextureWrapMode};
+
+#[derive(Default)]
+pub(crate) struct Textures {
+    pub paints: AHashMap<TextureId, PaintHandle>,
+    pub wrap_modes: AHashMap<TextureId, TextureWrapMode>,
+    /// Id of the next image added with [`crate::Painter::load_image`].
+    pub next_user_id: u64,
+    /// Number of frames whose textures were uploaded, shared by all painters using the store.
+    pub frame: u64,
+    /// Textures freed by the last frame. They are removed when the textures of the next frame
+    /// are uploaded, so other painters can still paint the last frame.
+    pub pending_free: Vec<TextureId>,
+}
+
+impl Textures {
+    pub fn free_pending(&mut self) {
+        for id in std::mem::take(&mut self.pending_free) {
+            self.paints.remove(&id);
+            self.wrap_modes.remove(&id);
+        }
+    }
+}
+
+/// The textures uploaded by one or more [`crate::Painter`]s.
+///
+/// Cloning a store returns a new reference to the same textures. To paint a frame with several
+/// painters sharing a store, e.g. to a window and a thumbnail, clone the
+/// [`crate::FrameOutput`] returned by [`crate::EguiSkia::run_detached`] and pass it to
+/// [`crate::Painter::paint_frame`] of each painter. Its textures are uploaded once, by whichever
+/// painter paints it first. As [`TextureId`]s are only unique within an [`egui::Context`],
+/// painters sharing a store must paint the output of the same context.
+#[derive(Clone, Default)]
+pub struct TextureStore {
+    textures: Arc<Mutex<Textures>>,
+}
+
+impl TextureStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Textures> {
+        self.textures.lock().unwrap()
+    }
+
+    /// Returns the number of textures in the store.
+    pub fn len(&self) -> usize {
+        self.lock().paints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if both stores refer to the same textures.
+    pub fn ptr_eq(&self, other: &TextureStore) -> bool {
+        Arc::ptr_eq(&self.textures, &other.textures)
+    }
+}
@@ -0,0 +1,289 @@
+use egui::epaint::{Mesh, Mesh16, Vertex};
+use egui::{Color32, Pos2, Rect};
+use skia_safe::{
+    ClipOp, IRect, Image, Matrix, MipmapMode, Paint, SamplingOptions, Surface, TileMode,
+};
+
+use crate::painter::TextureWrapMode;
+
+/// Pixels of the neighbouring tiles included on each side of a tile, so linear filtering is
+/// seamless at the tile edges.
+const TILE_BORDER: i32 = 1;
+
+/// A part of an image larger than the maximum texture size, see [`split_into_tiles`].
+pub(crate) struct Tile {
+    /// The pixels of the tile, including a border of [`TILE_BORDER`] pixels.
+    pub image: Image,
+    /// Samples the tile at the uv coordinates of the whole image.
+    pub paint: Paint,
+    /// The part of the image this tile is drawn for, in pixels and without the border.
+    rect: IRect,
+    /// `rect` in uv coordinates. Tiles at the edges of the image extend to infinity, so uv
+    /// coordinates outside of the image are clamped to its edge pixels.
+    uv_rect: Rect,
+}
+
+/// Splits an image larger than `max_texture_side` into tiles that each fit into a texture.
+///
+/// Meshes using the image are split with [`split_mesh`] and drawn once per tile, with a single
+/// image shader each.
+pub(crate) fn split_into_tiles(
+    image: &Image,
+    max_texture_side: i32,
+    sampling_options: SamplingOptions,
+) -> Option<Vec<Tile>> {
+    let (width, height) = (image.width(), image.height());
+    let bounds = IRect::from_wh(width, height);
+    let tile_side = (max_texture_side - 2 * TILE_BORDER).max(1);
+    let uv_matrix = Matrix::scale((1.0 / width as f32, 1.0 / height as f32));
+
+    let mut tiles = Vec::new();
+
+    for y in (0..height).step_by(tile_side as usize) {
+        for x in (0..width).step_by(tile_side as usize) {
+            let rect = IRect::from_xywh(x, y, tile_side.min(width - x), tile_side.min(height - y));
+            let bordered =
+                IRect::intersect(&rect.with_outset((TILE_BORDER, TILE_BORDER)), &bounds)?;
+
+            let mut tile = image.new_subset(bordered)?;
+            if sampling_options.mipmap != MipmapMode::None {
+                tile = tile.with_default_mipmaps().unwrap_or(tile);
+            }
+
+            let mut tile_matrix = uv_matrix;
+            tile_matrix.pre_translate((bordered.left as f32, bordered.top as f32));
+            let shader = tile.to_shader(
+                (TileMode::Clamp, TileMode::Clamp),
+                sampling_options,
+                &tile_matrix,
+            )?;
+            let mut paint = Paint::default();
+            paint.set_shader(shader);
+
+            let uv = |value: i32, size: i32, edge: f32| {
+                if value == 0 || value == size {
+                    edge
+                } else {
+                    value as f32 / size as f32
+                }
+            };
+            let uv_rect = Rect::from_min_max(
+                Pos2::new(
+                    uv(rect.left, width, f32::NEG_INFINITY),
+                    uv(rect.top, height, f32::NEG_INFINITY),
+                ),
+                Pos2::new(
+                    uv(rect.right, width, f32::INFINITY),
+                    uv(rect.bottom, height, f32::INFINITY),
+                ),
+            );
+
+            tiles.push(Tile {
+                image: tile,
+                paint,
+                rect,
+                uv_rect,
+            });
+        }
+    }
+
+    Some(tiles)
+}
+
+/// Puts the image split by [`split_into_tiles`] back together, e.g. to update a part of it.
+pub(crate) fn assemble_tiles(tiles: &[Tile]) -> Option<Image> {
+    let width = tiles.iter().map(|tile| tile.rect.right).max()?;
+    let height = tiles.iter().map(|tile| tile.rect.bottom).max()?;
+    let info = tiles[0].image.image_info().with_dimensions((width, height));
+
+    let mut surface = Surface::new_raster(&info, None, None)?;
+    let canvas = surface.canvas();
+    for tile in tiles {
+        let bordered = tile.rect.with_outset((TILE_BORDER, TILE_BORDER));
+        canvas.save();
+        canvas.clip_rect(skia_safe::Rect::from(tile.rect), ClipOp::default(), false);
+        canvas.draw_image(
+            &tile.image,
+            (bordered.left.max(0) as f32, bordered.top.max(0) as f32),
+            None,
+        );
+        canvas.restore();
+    }
+
+    Some(surface.image_snapshot())
+}
+
+/// Splits a mesh using a tiled image into one mesh per tile, indexed like `tiles`, so every
+/// triangle only samples the tile it is drawn with.
+///
+/// Triangles are cut at the edges of the tiles. With [`TextureWrapMode::Repeat`] and
+/// [`TextureWrapMode::MirroredRepeat`] they are first cut at every repetition of the image, and
+/// their uv coordinates are moved into the image.
+pub(crate) fn split_mesh(mesh: &Mesh16, tiles: &[Tile], wrap_mode: TextureWrapMode) -> Vec<Mesh> {
+    let mut meshes: Vec<Mesh> = tiles
+        .iter()
+        .map(|_| Mesh::with_texture(mesh.texture_id))
+        .collect();
+
+    for triangle in mesh.indices.chunks_exact(3) {
+        let triangle: Vec<Vertex> = triangle
+            .iter()
+            .map(|index| mesh.vertices[*index as usize])
+            .collect();
+
+        for polygon in wrap_polygon(triangle, wrap_mode) {
+            let bounds = uv_bounds(&polygon);
+            for (tile, tile_mesh) in tiles.iter().zip(&mut meshes) {
+                if tile.uv_rect.intersects(bounds) {
+                    add_polygon(tile_mesh, clip_polygon(polygon.clone(), tile.uv_rect));
+                }
+            }
+        }
+    }
+
+    meshes
+}
+
+/// Cuts the polygon at every repetition of the image it spans and moves the uv coordinates of
+/// the parts into the image. Clamped polygons are returned as they are.
+fn wrap_polygon(polygon: Vec<Vertex>, wrap_mode: TextureWrapMode) -> Vec<Vec<Vertex>> {
+    let mirrored = match wrap_mode {
+        TextureWrapMode::ClampToEdge => return vec![polygon],
+        TextureWrapMode::Repeat => false,
+        TextureWrapMode::MirroredRepeat => true,
+    };
+
+    let bounds = uv_bounds(&polygon);
+    let periods = |min: f32, max: f32| {
+        let first = min.floor() as i64;
+        first..(max.ceil() as i64).max(first + 1)
+    };
+
+    let mut parts = Vec::new();
+    for y in periods(bounds.min.y, bounds.max.y) {
+        for x in periods(bounds.min.x, bounds.max.x) {
+            let period = Rect::from_min_max(
+                Pos2::new(x as f32, y as f32),
+                Pos2::new((x + 1) as f32, (y + 1) as f32),
+            );
+            let mut part = clip_polygon(polygon.clone(), period);
+            if part.is_empty() {
+                continue;
+            }
+
+            let wrap = |value: f32, period: i64| {
+                if mirrored && period.rem_euclid(2) == 1 {
+                    (period + 1) as f32 - value
+                } else {
+                    value - period as f32
+                }
+            };
+            for vertex in &mut part {
+                vertex.uv = Pos2::new(wrap(vertex.uv.x, x), wrap(vertex.uv.y, y));
+            }
+            parts.push(part);
+        }
+    }
+    parts
+}
+
+fn uv_bounds(polygon: &[Vertex]) -> Rect {
+    Rect::from_points(&polygon.iter().map(|vertex| vertex.uv).collect::<Vec<_>>())
+}
+
+/// Clips a convex polygon to the uv rect (Sutherland-Hodgman), interpolating the positions and
+/// colors of the new vertices.
+fn clip_polygon(mut polygon: Vec<Vertex>, uv_rect: Rect) -> Vec<Vertex> {
+    // Each edge of the rect, as the axis (0 = u, 1 = v), the bound, and whether values above
+    // the bound are inside
+    let planes = [
+        (0, uv_rect.min.x, true),
+        (0, uv_rect.max.x, false),
+        (1, uv_rect.min.y, true),
+        (1, uv_rect.max.y, false),
+    ];
+
+    for (axis, bound, above) in planes {
+        if bound.is_infinite() {
+            continue;
+        }
+        let inside = |vertex: &Vertex| {
+            let value = if axis == 0 { vertex.uv.x } else { vertex.uv.y };
+            if above {
+                value >= bound
+            } else {
+                value <= bound
+            }
+        };
+
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (index, current) in polygon.iter().enumerate() {
+            let previous = &polygon[(index + polygon.len() - 1) % polygon.len()];
+            match (inside(previous), inside(current)) {
+                (true, true) => clipped.push(*current),
+                (true, false) => clipped.push(intersect(previous, current, axis, bound)),
+                (false, true) => {
+                    clipped.push(intersect(previous, current, axis, bound));
+                    clipped.push(*current);
+                }
+                (false, false) => {}
+            }
+        }
+        polygon = clipped;
+
+        if polygon.is_empty() {
+            break;
+        }
+    }
+
+    polygon
+}
+
+/// The point of the edge between `a` and `b` at `bound` on the axis.
+fn intersect(a: &Vertex, b: &Vertex, axis: usize, bound: f32) -> Vertex {
+    // Interpolate in a fixed direction, so the neighbouring tile, which walks the edge the other
+    // way around, gets exactly the same vertex and no gaps open up between the tiles.
+    let (a, b) = if (a.uv.x, a.uv.y) <= (b.uv.x, b.uv.y) {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let (value_a, value_b) = if axis == 0 {
+        (a.uv.x, b.uv.x)
+    } else {
+        (a.uv.y, b.uv.y)
+    };
+    let t = (bound - value_a) / (value_b - value_a);
+
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    let mut uv = a.uv + (b.uv - a.uv) * t;
+    // Exactly on the bound, so the vertex counts as inside for the following planes
+    if axis == 0 {
+        uv.x = bound;
+    } else {
+        uv.y = bound;
+    }
+
+    Vertex {
+        pos: a.pos + (b.pos - a.pos) * t,
+        uv,
+        color: Color32::from_rgba_premultiplied(
+            lerp(a.color.r(), b.color.r()),
+            lerp(a.color.g(), b.color.g()),
+            lerp(a.color.b(), b.color.b()),
+            lerp(a.color.a(), b.color.a()),
+        ),
+    }
+}
+
+/// Adds a convex polygon to the mesh as a triangle fan.
+fn add_polygon(mesh: &mut Mesh, polygon: Vec<Vertex>) {
+    if polygon.len() < 3 {
+        return;
+    }
+    let first = mesh.vertices.len() as u32;
+    for index in 1..polygon.len() as u32 - 1 {
+        mesh.add_triangle(first, first + index, first + index + 1);
+    }
+    mesh.vertices.extend(polygon);
+}
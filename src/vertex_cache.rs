@@ -0,0 +1,106 @@
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use egui::epaint::ahash::{AHashMap, AHasher, RandomState};
+use egui::epaint::{Mesh16, Vertex};
+use skia_safe::Vertices;
+
+/// Identifies the vertices built for a mesh, see [`VertexCache::key`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VertexKey {
+    hash: u64,
+    /// A second hash with different keys and the size of the mesh, so meshes whose `hash`
+    /// collides aren't mistaken for each other.
+    check: u64,
+    vertex_count: usize,
+    index_count: usize,
+}
+
+struct CacheEntry {
+    key: VertexKey,
+    vertices: Vertices,
+    last_used: u64,
+}
+
+/// Keeps the [`Vertices`] built for a mesh around between frames, so static parts of the ui
+/// don't have to be converted again every frame.
+pub(crate) struct VertexCache {
+    max_unused_frames: u64,
+    frame: u64,
+    entries: AHashMap<u64, CacheEntry>,
+}
+
+impl VertexCache {
+    pub fn new(max_unused_frames: u64) -> Self {
+        Self {
+            max_unused_frames,
+            frame: 0,
+            entries: AHashMap::new(),
+        }
+    }
+
+    /// Returns the key of the vertices built for the mesh.
+    ///
+    /// `remap_white_uv` is part of the key, as it changes the vertices built for the same mesh.
+    pub fn key(mesh: &Mesh16, remap_white_uv: bool) -> VertexKey {
+        let hash_mesh = |mut hasher: AHasher| {
+            remap_white_uv.hash(&mut hasher);
+            mesh.texture_id.hash(&mut hasher);
+            mesh.indices.hash(&mut hasher);
+            hash_vertices(&mesh.vertices, &mut hasher);
+            hasher.finish()
+        };
+
+        VertexKey {
+            hash: hash_mesh(AHasher::default()),
+            check: hash_mesh(RandomState::with_seeds(1, 2, 3, 4).build_hasher()),
+            vertex_count: mesh.vertices.len(),
+            index_count: mesh.indices.len(),
+        }
+    }
+
+    /// Returns the cached vertices for the key and marks them as used in this frame.
+    pub fn get(&mut self, key: VertexKey) -> Option<Vertices> {
+        let entry = self.entries.get_mut(&key.hash)?;
+        if entry.key != key {
+            return None;
+        }
+        entry.last_used = self.frame;
+        Some(entry.vertices.clone())
+    }
+
+    /// Caches the vertices, replacing an entry whose hash collides with the key.
+    pub fn insert(&mut self, key: VertexKey, vertices: Vertices) {
+        self.entries.insert(
+            key.hash,
+            CacheEntry {
+                key,
+                vertices,
+                last_used: self.frame,
+            },
+        );
+    }
+
+    /// Evicts entries that haven't been used in the last `max_unused_frames` frames.
+    pub fn end_frame(&mut self) {
+        let frame = self.frame;
+        let max_unused_frames = self.max_unused_frames;
+        self.entries
+            .retain(|_, entry| frame - entry.last_used <= max_unused_frames);
+        self.frame += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// [`Vertex`] contains floats and therefore doesn't implement [`Hash`], so hash their bits.
+pub(crate) fn hash_vertices(vertices: &[Vertex], state: &mut impl Hasher) {
+    for vertex in vertices {
+        vertex.pos.x.to_bits().hash(state);
+        vertex.pos.y.to_bits().hash(state);
+        vertex.uv.x.to_bits().hash(state);
+        vertex.uv.y.to_bits().hash(state);
+        vertex.color.hash(state);
+    }
+}
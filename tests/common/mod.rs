@@ -0,0 +1,83 @@
+// Each test crate uses a different part of the helpers
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use skia_safe::image::CachingHint;
+use skia_safe::{EncodedImageFormat, Image};
+
+/// Compares `image` against `tests/reference/<name>.png`, allowing each color channel to differ
+/// by `tolerance`.
+///
+/// Missing references are written and the test fails, so they can be checked and committed.
+/// Set `EGUI_SKIA_BLESS=1` to overwrite the references after an intended change. Images that
+/// don't match are written to the target directory for inspection.
+pub fn assert_matches_reference(name: &str, image: &Image, tolerance: u8) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/reference")
+        .join(format!("{name}.png"));
+
+    if std::env::var_os("EGUI_SKIA_BLESS").is_some() || !path.exists() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, encode(image)).unwrap();
+        assert!(
+            std::env::var_os("EGUI_SKIA_BLESS").is_some(),
+            "wrote the missing reference {}, check it and commit it",
+            path.display()
+        );
+        return;
+    }
+
+    let reference = Image::from_encoded(skia_safe::Data::new_copy(&std::fs::read(&path).unwrap()))
+        .expect("Failed to decode reference");
+    assert_eq!(
+        reference.dimensions(),
+        image.dimensions(),
+        "size differs from {}",
+        path.display()
+    );
+
+    let max_difference = max_difference(image, &reference);
+    if max_difference > tolerance {
+        let actual = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.png"));
+        std::fs::write(&actual, encode(image)).unwrap();
+        panic!(
+            "{} differs from {} by up to {max_difference}, wrote it to {}",
+            name,
+            path.display(),
+            actual.display()
+        );
+    }
+}
+
+/// The largest difference of a color channel between the images, which must have the same size.
+pub fn max_difference(a: &Image, b: &Image) -> u8 {
+    let (a, b) = (pixels(a), pixels(b));
+    a.iter()
+        .zip(&b)
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap_or_default()
+}
+
+/// The pixels of the image as premultiplied n32 colors.
+pub fn pixels(image: &Image) -> Vec<u8> {
+    let info = skia_safe::ImageInfo::new_n32_premul(image.dimensions(), None);
+    let mut pixels = vec![0u8; info.compute_min_byte_size()];
+    assert!(image.read_pixels(
+        &info,
+        pixels.as_mut_slice(),
+        info.min_row_bytes(),
+        (0, 0),
+        CachingHint::Allow,
+    ));
+    pixels
+}
+
+fn encode(image: &Image) -> Vec<u8> {
+    image
+        .encode_to_data(EncodedImageFormat::PNG)
+        .expect("Failed to encode image")
+        .as_bytes()
+        .to_vec()
+}
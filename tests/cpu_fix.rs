@@ -0,0 +1,159 @@
+mod common;
+
+use egui::{Color32, ColorImage, LayerId, Pos2, Rect, TextureOptions};
+use egui_skia::{rasterize, CpuFix, DebugOverlay, RasterizeOptions};
+use skia_safe::{Color, Image, Surface};
+
+fn rasterize_with_cpu_fix(size: (i32, i32), ui: impl FnMut(&egui::Context)) -> Surface {
+    render(CpuFix::Always, DebugOverlay::default(), size, ui)
+}
+
+fn render(
+    cpu_fix: CpuFix,
+    debug_overlay: DebugOverlay,
+    size: (i32, i32),
+    ui: impl FnMut(&egui::Context),
+) -> Surface {
+    rasterize(
+        size,
+        ui,
+        Some(RasterizeOptions {
+            cpu_fix,
+            debug_overlay,
+            ..Default::default()
+        }),
+    )
+}
+
+/// Solid fills and text, which both use the font atlas.
+fn solid_fills_and_text(cpu_fix: CpuFix, debug_overlay: DebugOverlay) -> Image {
+    render(cpu_fix, debug_overlay, (300, 100), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Text");
+            let _ = ui.button("Button");
+        });
+    })
+    .image_snapshot()
+}
+
+/// Only shows the meshes drawn with remapped white uvs.
+fn remapped_overlay() -> DebugOverlay {
+    DebugOverlay {
+        white_uv_remapped: true,
+        ..Default::default()
+    }
+}
+
+/// Solid fills use the white uv of the font atlas, which the workaround remaps. They have to keep
+/// the exact colors of their vertices.
+#[test]
+fn solid_fills_keep_their_vertex_colors() {
+    let colors = [
+        Color32::RED,
+        Color32::from_rgb(10, 200, 90),
+        Color32::from_rgb(30, 30, 200),
+        Color32::WHITE,
+        Color32::from_gray(128),
+    ];
+
+    let mut surface = rasterize_with_cpu_fix((400, 100), |ctx| {
+        let painter = ctx.layer_painter(LayerId::background());
+        for (index, color) in colors.iter().enumerate() {
+            let min = Pos2::new(index as f32 * 80.0, 0.0);
+            painter.rect_filled(
+                Rect::from_min_size(min, egui::vec2(80.0, 100.0)),
+                8.0,
+                *color,
+            );
+        }
+    });
+
+    let image = surface.image_snapshot();
+    let pixmap = image.peek_pixels().unwrap();
+    for (index, color) in colors.iter().enumerate() {
+        let center = (index as i32 * 80 + 40, 50);
+        assert_eq!(
+            pixmap.get_color(center),
+            Color::from_argb(color.a(), color.r(), color.g(), color.b()),
+            "fill {index}"
+        );
+    }
+}
+
+#[test]
+fn color_test_matches_reference() {
+    let mut color_test = egui_demo_lib::ColorTest::default();
+    let mut surface = rasterize_with_cpu_fix((800, 2000), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| color_test.ui(ui));
+    });
+
+    common::assert_matches_reference("color_test", &surface.image_snapshot(), 2);
+}
+
+#[test]
+fn demo_windows_match_reference() {
+    let mut demo = egui_demo_lib::DemoWindows::default();
+    let mut surface = rasterize_with_cpu_fix((1024, 756), |ctx| demo.ui(ctx));
+
+    common::assert_matches_reference("demo_windows", &surface.image_snapshot(), 2);
+}
+
+/// Without a gpu context, [`CpuFix::Auto`] applies the workaround like [`CpuFix::Always`].
+#[test]
+fn auto_applies_the_workaround_on_raster_surfaces() {
+    let auto = solid_fills_and_text(CpuFix::Auto, remapped_overlay());
+    let always = solid_fills_and_text(CpuFix::Always, remapped_overlay());
+    assert_eq!(common::max_difference(&auto, &always), 0);
+
+    // The overlay shows the remapped meshes
+    let without_overlay = solid_fills_and_text(CpuFix::Auto, DebugOverlay::default());
+    assert_ne!(common::max_difference(&auto, &without_overlay), 0);
+}
+
+/// [`CpuFix::Never`] draws the font atlas with its usual paint, no mesh is remapped.
+#[test]
+fn never_doesnt_remap_white_uvs() {
+    let with_overlay = solid_fills_and_text(CpuFix::Never, remapped_overlay());
+    let without_overlay = solid_fills_and_text(CpuFix::Never, DebugOverlay::default());
+    assert_eq!(common::max_difference(&with_overlay, &without_overlay), 0);
+}
+
+/// User images don't use the white uv, so the workaround has to draw them exactly like the gpu
+/// path, linearly filtered.
+#[test]
+fn linear_images_match_without_the_workaround() {
+    let render_image = |cpu_fix| {
+        let checkerboard = ColorImage::from_rgba_unmultiplied(
+            [2, 2],
+            &[
+                0, 0, 0, 255, 255, 255, 255, 255, //
+                255, 255, 255, 255, 0, 0, 0, 255,
+            ],
+        );
+        let mut texture = None;
+        render(cpu_fix, DebugOverlay::default(), (100, 100), |ctx| {
+            let texture = texture.get_or_insert_with(|| {
+                ctx.load_texture("checkerboard", checkerboard.clone(), TextureOptions::LINEAR)
+            });
+            ctx.layer_painter(LayerId::background()).image(
+                texture.id(),
+                ctx.screen_rect(),
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        })
+        .image_snapshot()
+    };
+
+    let always = render_image(CpuFix::Always);
+    let never = render_image(CpuFix::Never);
+    assert_eq!(common::max_difference(&always, &never), 0);
+
+    // Magnified with linear filtering, there are grays between the black and white texels
+    let pixmap = always.peek_pixels().unwrap();
+    let gray = pixmap.get_color((50, 25));
+    assert!(
+        (64..192).contains(&gray.r()),
+        "{gray:?} isn't filtered linearly"
+    );
+}
@@ -0,0 +1,64 @@
+use egui::{Pos2, Rect};
+use egui_skia::EguiSkia;
+use skia_safe::{Contains, IRect, Surface};
+
+const SIZE: (i32, i32) = (800, 400);
+/// Outset of the window rect that covers its shadow.
+const SHADOW_MARGIN: i32 = 50;
+
+/// Runs and paints a frame with a window at `pos`, returning the damage and the window's rect.
+fn frame(egui_skia: &mut EguiSkia, surface: &mut Surface, pos: Pos2) -> (Vec<IRect>, Rect) {
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(SIZE.0 as f32, SIZE.1 as f32)].into()),
+        pixels_per_point: Some(1.0),
+        ..Default::default()
+    };
+    let mut window_rect = Rect::NOTHING;
+    egui_skia.run(input, |ctx| {
+        let response = egui::Window::new("Window")
+            .current_pos(pos)
+            .show(ctx, |ui| ui.label("Label"));
+        window_rect = response.unwrap().response.rect;
+    });
+    (egui_skia.paint(surface.canvas()), window_rect)
+}
+
+fn with_shadow(rect: Rect) -> IRect {
+    IRect::new(
+        rect.min.x as i32 - SHADOW_MARGIN,
+        rect.min.y as i32 - SHADOW_MARGIN,
+        rect.max.x as i32 + SHADOW_MARGIN,
+        rect.max.y as i32 + SHADOW_MARGIN,
+    )
+}
+
+#[test]
+fn only_changes_are_repainted() {
+    let mut surface = Surface::new_raster_n32_premul(SIZE).unwrap();
+    let mut egui_skia = EguiSkia::new();
+    egui_skia.set_damage_tracking(true);
+
+    let first = Pos2::new(20.0, 20.0);
+    let (damage, _) = frame(&mut egui_skia, &mut surface, first);
+    assert_eq!(damage, vec![IRect::from_size(SIZE)]);
+
+    // egui shows new windows from the second frame on
+    let mut old_rect = Rect::NOTHING;
+    for _ in 0..3 {
+        old_rect = frame(&mut egui_skia, &mut surface, first).1;
+    }
+
+    let (damage, _) = frame(&mut egui_skia, &mut surface, first);
+    assert!(damage.is_empty(), "an identical frame repainted {damage:?}");
+
+    let (damage, new_rect) = frame(&mut egui_skia, &mut surface, Pos2::new(420.0, 20.0));
+    let (old_bounds, new_bounds) = (with_shadow(old_rect), with_shadow(new_rect));
+    assert!(!IRect::intersects(&old_bounds, &new_bounds));
+    assert_eq!(damage.len(), 2, "{damage:?}");
+    for rect in &damage {
+        assert!(
+            old_bounds.contains(rect) || new_bounds.contains(rect),
+            "{rect:?} is outside of the old and new window bounds"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use egui_skia::{rasterize, FontAtlasFormat, PainterOptions, RasterizeOptions};
+use skia_safe::Image;
+
+fn render(font_atlas_format: FontAtlasFormat) -> Image {
+    let mut demo = egui_demo_lib::DemoWindows::default();
+
+    rasterize(
+        (1024, 756),
+        |ctx| demo.ui(ctx),
+        Some(RasterizeOptions {
+            painter_options: PainterOptions {
+                font_atlas_format,
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+    )
+    .image_snapshot()
+}
+
+/// The alpha only font atlas has to look the same as the rgba one.
+#[test]
+fn alpha_atlas_matches_rgba_atlas() {
+    let rgba = render(FontAtlasFormat::Rgba);
+    let alpha = render(FontAtlasFormat::Alpha);
+
+    let max_difference = common::max_difference(&rgba, &alpha);
+    assert!(
+        max_difference <= 2,
+        "the atlas formats differ by up to {max_difference}"
+    );
+}
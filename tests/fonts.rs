@@ -0,0 +1,82 @@
+use egui::{Color32, FontFamily};
+use egui_skia::{rasterize, ColorText, FontDefinitionsBuilder};
+use skia_safe::{Color, FontMgr, Typeface};
+
+const HACK: &[u8] = include_bytes!("fonts/Hack-Regular.ttf");
+/// A square with a red left half and a green right half for 🦀, see `fonts/make_color_font.py`.
+const COLOR_TEST: &[u8] = include_bytes!("fonts/ColorTest.ttf");
+
+fn typeface(data: &[u8]) -> Typeface {
+    FontMgr::new()
+        .new_from_data(data, None)
+        .expect("Failed to load bundled font")
+}
+
+#[test]
+fn bundled_fonts_come_before_the_defaults() {
+    let mut builder = FontDefinitionsBuilder::new();
+    builder.push_font_data(FontFamily::Monospace, "Bundled Hack", HACK.to_vec());
+    assert!(builder.push_typeface(FontFamily::Proportional, &typeface(COLOR_TEST)));
+    let definitions = builder.build();
+
+    let defaults = egui::FontDefinitions::default();
+    for (family, first) in [
+        (FontFamily::Monospace, "Bundled Hack"),
+        (FontFamily::Proportional, "EguiSkiaColorTest#0"),
+    ] {
+        let fonts = &definitions.families[&family];
+        assert_eq!(fonts[0], first);
+        assert_eq!(fonts[1..], defaults.families[&family][..]);
+        assert!(definitions.font_data.contains_key(first));
+    }
+
+    // egui can load the fonts
+    rasterize(
+        (200, 100),
+        |ctx| {
+            ctx.set_fonts(definitions.clone());
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.label("🦀");
+                ui.code("Hack");
+            });
+        },
+        None,
+    );
+}
+
+#[test]
+fn color_text_keeps_the_glyph_colors() {
+    let typefaces = [typeface(COLOR_TEST), typeface(HACK)];
+    let color_text =
+        ColorText::with_typefaces(&typefaces, &FontMgr::new(), "🦀A", 48.0, Color32::WHITE);
+    assert!(color_text.size().x > 48.0);
+
+    let mut surface = rasterize(
+        (200, 100),
+        |ctx| {
+            egui::CentralPanel::default()
+                .frame(egui::Frame::none().fill(Color32::BLACK))
+                .show(ctx, |ui| {
+                    color_text.show(ui);
+                });
+        },
+        None,
+    );
+    let image = surface.image_snapshot();
+    let pixels = image.peek_pixels().unwrap();
+
+    let colors: Vec<Color> = (0..image.height())
+        .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .map(|point| pixels.get_color(point))
+        .collect();
+    let has = |predicate: fn(&Color) -> bool| colors.iter().any(predicate);
+
+    // The layers of the color glyph, from the palette of the font
+    assert!(has(|c| c.r() > 240 && c.g() < 16 && c.b() < 16), "no red");
+    assert!(has(|c| c.g() > 240 && c.r() < 16 && c.b() < 16), "no green");
+    // The glyph of Hack, in the text color
+    assert!(
+        has(|c| c.r() > 240 && c.g() > 240 && c.b() > 240),
+        "no white"
+    );
+}
@@ -0,0 +1,210 @@
+#!/usr/bin/env python3
+"""Writes ColorTest.ttf, a minimal COLRv0 color font used by tests/fonts.rs.
+
+U+1F980 (crab) and U+2605 (black star) map to a square whose left half is red and whose right
+half is green. Without COLR support the square is drawn in the text color.
+
+Run from this directory: python3 make_color_font.py
+"""
+
+import struct
+
+UNITS_PER_EM = 1000
+ASCENDER = 800
+DESCENDER = -200
+ADVANCE = 1000
+CHARACTERS = [0x2605, 0x1F980]
+FAMILY = "EguiSkiaColorTest"
+
+# .notdef, the base glyph, and its two layers, as (x_min, y_min, x_max, y_max) rects
+GLYPHS = [None, (50, 0, 950, 700), (50, 0, 500, 700), (500, 0, 950, 700)]
+# Palette colors as BGRA
+PALETTE = [(0, 0, 255, 255), (0, 255, 0, 255)]
+
+
+def rect_glyph(rect):
+    if rect is None:
+        return b""
+    x_min, y_min, x_max, y_max = rect
+    points = [(x_min, y_min), (x_min, y_max), (x_max, y_max), (x_max, y_min)]
+    data = struct.pack(">hhhhh", 1, x_min, y_min, x_max, y_max)
+    data += struct.pack(">HH", len(points) - 1, 0)
+    # On curve points with 16 bit deltas
+    data += bytes([0x01] * len(points))
+    previous = (0, 0)
+    xs, ys = b"", b""
+    for x, y in points:
+        xs += struct.pack(">h", x - previous[0])
+        ys += struct.pack(">h", y - previous[1])
+        previous = (x, y)
+    data += xs + ys
+    return data + b"\0" * (-len(data) % 4)
+
+
+def glyf_and_loca():
+    glyf, offsets = b"", [0]
+    for rect in GLYPHS:
+        glyf += rect_glyph(rect)
+        offsets.append(len(glyf))
+    return glyf, struct.pack(">%dI" % len(offsets), *offsets)
+
+
+def head():
+    return struct.pack(
+        ">IIIIHHqqhhhhHHhhh",
+        0x00010000,  # version
+        0x00010000,  # font revision
+        0,  # checksum adjustment, filled in by build()
+        0x5F0F3CF5,  # magic number
+        0x000B,  # baseline and left sidebearing at 0, integer scaling
+        UNITS_PER_EM,
+        0,  # created
+        0,  # modified
+        50, 0, 950, 700,  # bounds
+        0,  # mac style
+        8,  # smallest readable size
+        2,  # font direction hint
+        1,  # long loca offsets
+        0,  # glyph data format
+    )
+
+
+def hhea():
+    return struct.pack(
+        ">IhhhHhhhhhhhhhhhH",
+        0x00010000,
+        ASCENDER,
+        DESCENDER,
+        0,  # line gap
+        ADVANCE,
+        50,  # min left sidebearing
+        50,  # min right sidebearing
+        950,  # x max extent
+        1, 0, 0,  # caret
+        0, 0, 0, 0,  # reserved
+        0,  # metric data format
+        len(GLYPHS),
+    )
+
+
+def maxp():
+    return struct.pack(
+        ">IHHHHHHHHHHHHHH", 0x00010000, len(GLYPHS), 4, 1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0
+    )
+
+
+def hmtx():
+    return b"".join(
+        struct.pack(">Hh", ADVANCE, rect[0] if rect else 0) for rect in GLYPHS
+    )
+
+
+def cmap():
+    # Format 4 for the characters in the basic multilingual plane
+    bmp = [c for c in CHARACTERS if c <= 0xFFFF]
+    segments = [(c, c, (1 - c) % 0x10000) for c in bmp] + [(0xFFFF, 0xFFFF, 1)]
+    seg_count = len(segments)
+    search_range = 2 * 2 ** (seg_count.bit_length() - 1)
+    entry_selector = seg_count.bit_length() - 1
+    format4 = struct.pack(">%dH" % seg_count, *[end for _, end, _ in segments])
+    format4 += struct.pack(">H", 0)
+    format4 += struct.pack(">%dH" % seg_count, *[start for start, _, _ in segments])
+    format4 += struct.pack(">%dH" % seg_count, *[delta for _, _, delta in segments])
+    format4 += struct.pack(">%dH" % seg_count, *[0] * seg_count)
+    format4 = (
+        struct.pack(
+            ">HHHHHHH",
+            4,
+            14 + len(format4),
+            0,
+            seg_count * 2,
+            search_range,
+            entry_selector,
+            seg_count * 2 - search_range,
+        )
+        + format4
+    )
+
+    # Format 12 for all characters
+    groups = b"".join(struct.pack(">III", c, c, 1) for c in sorted(CHARACTERS))
+    format12 = struct.pack(">HHIII", 12, 0, 16 + len(groups), 0, len(CHARACTERS)) + groups
+
+    header = struct.pack(">HH", 0, 2)
+    header += struct.pack(">HHI", 3, 1, 4 + 2 * 8)
+    header += struct.pack(">HHI", 3, 10, 4 + 2 * 8 + len(format4))
+    return header + format4 + format12
+
+
+def name():
+    names = [(1, FAMILY), (2, "Regular"), (4, FAMILY), (6, FAMILY)]
+    strings, records = b"", b""
+    for name_id, value in names:
+        encoded = value.encode("utf-16-be")
+        records += struct.pack(">HHHHHH", 3, 1, 0x409, name_id, len(encoded), len(strings))
+        strings += encoded
+    return struct.pack(">HHH", 0, len(names), 6 + len(records)) + records + strings
+
+
+def post():
+    return struct.pack(">IIhhIIIII", 0x00030000, 0, -100, 50, 0, 0, 0, 0, 0)
+
+
+def colr():
+    base_glyphs = struct.pack(">HHH", 1, 0, 2)
+    layers = struct.pack(">HHHH", 2, 0, 3, 1)
+    return struct.pack(">HHIIH", 0, 1, 14, 14 + len(base_glyphs), 2) + base_glyphs + layers
+
+
+def cpal():
+    colors = b"".join(struct.pack(">BBBB", *color) for color in PALETTE)
+    return struct.pack(">HHHHIH", 0, len(PALETTE), 1, len(PALETTE), 14, 0) + colors
+
+
+def checksum(data):
+    data += b"\0" * (-len(data) % 4)
+    return sum(struct.unpack(">%dI" % (len(data) // 4), data)) & 0xFFFFFFFF
+
+
+def build():
+    glyf, loca = glyf_and_loca()
+    tables = {
+        b"COLR": colr(),
+        b"CPAL": cpal(),
+        b"cmap": cmap(),
+        b"glyf": glyf,
+        b"head": head(),
+        b"hhea": hhea(),
+        b"hmtx": hmtx(),
+        b"loca": loca,
+        b"maxp": maxp(),
+        b"name": name(),
+        b"post": post(),
+    }
+
+    num_tables = len(tables)
+    entry_selector = num_tables.bit_length() - 1
+    search_range = 16 * 2**entry_selector
+    directory = struct.pack(
+        ">IHHHH", 0x00010000, num_tables, search_range, entry_selector,
+        num_tables * 16 - search_range,
+    )
+
+    offset = 12 + 16 * num_tables
+    body = b""
+    for tag in sorted(tables):
+        data = tables[tag]
+        directory += struct.pack(">4sIII", tag, checksum(data), offset + len(body), len(data))
+        body += data + b"\0" * (-len(data) % 4)
+
+    font = bytearray(directory + body)
+    head_offset = 12 + 16 * num_tables + sum(
+        len(tables[tag]) + (-len(tables[tag]) % 4) for tag in sorted(tables) if tag < b"head"
+    )
+    adjustment = (0xB1B0AFBA - checksum(bytes(font))) & 0xFFFFFFFF
+    font[head_offset + 8 : head_offset + 12] = struct.pack(">I", adjustment)
+    return bytes(font)
+
+
+if __name__ == "__main__":
+    with open("ColorTest.ttf", "wb") as file:
+        file.write(build())
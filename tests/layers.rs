@@ -0,0 +1,40 @@
+use egui::{LayerId, Pos2};
+use egui_skia::{EguiSkia, LayerStyle};
+use skia_safe::Surface;
+
+fn run(egui_skia: &mut EguiSkia) {
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(400.0, 300.0)].into()),
+        ..Default::default()
+    };
+    egui_skia.run(input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| ui.label("Panel"));
+        egui::Window::new("Window").show(ctx, |ui| ui.label("Label"));
+    });
+}
+
+/// The markers added by `run` are removed even if layer painting is disabled before painting.
+#[test]
+fn layer_painting_can_be_disabled_between_run_and_paint() {
+    let mut surface = Surface::new_raster_n32_premul((400, 300)).unwrap();
+    let mut egui_skia = EguiSkia::new();
+
+    egui_skia.set_layer_painting(true);
+    run(&mut egui_skia);
+    egui_skia.set_layer_painting(false);
+    egui_skia.paint(surface.canvas());
+
+    egui_skia.set_layer_painting(true);
+    run(&mut egui_skia);
+    egui_skia.set_layer_painting(false);
+    let mut layers = Vec::new();
+    egui_skia.paint_layers(surface.canvas(), |_, layer_id| {
+        layers.push(layer_id);
+        LayerStyle::default()
+    });
+    // Split with the markers of the run, not merged into the background
+    assert!(layers.len() > 1, "{layers:?}");
+    assert!(layers
+        .iter()
+        .any(|layer_id| *layer_id != LayerId::background()));
+}
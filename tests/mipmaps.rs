@@ -0,0 +1,74 @@
+use egui::{Color32, ColorImage, LayerId, Pos2, Rect, TextureOptions};
+use egui_skia::rasterize;
+
+const SIZE: usize = 512;
+/// The checkerboard is drawn into a square this large, about seven texels per pixel.
+const SIDE: f32 = 70.0;
+
+/// Returns the mean and variance of the red channel of a checkerboard drawn downscaled.
+fn downscaled_checkerboard(options: TextureOptions) -> (f32, f32) {
+    let checkerboard = ColorImage {
+        size: [SIZE, SIZE],
+        pixels: (0..SIZE * SIZE)
+            .map(|i| {
+                if (i % SIZE + i / SIZE) % 2 == 0 {
+                    Color32::BLACK
+                } else {
+                    Color32::WHITE
+                }
+            })
+            .collect(),
+    };
+
+    let mut texture = None;
+    let mut surface = rasterize(
+        (100, 100),
+        |ctx| {
+            let texture = texture.get_or_insert_with(|| {
+                ctx.load_texture("checkerboard", checkerboard.clone(), options)
+            });
+            ctx.layer_painter(LayerId::background()).image(
+                texture.id(),
+                Rect::from_min_size(Pos2::new(10.0, 10.0), egui::Vec2::splat(SIDE)),
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        },
+        None,
+    );
+
+    let image = surface.image_snapshot();
+    let pixmap = image.peek_pixels().unwrap();
+    // Leave out the pixels at the edges of the square, which are only partially covered
+    let inside = 12..(10 + SIDE as i32 - 2);
+    let values: Vec<f32> = inside
+        .clone()
+        .flat_map(|y| inside.clone().map(move |x| (x, y)))
+        .map(|point| pixmap.get_color(point).r() as f32)
+        .collect();
+
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f32>()
+        / values.len() as f32;
+    (mean, variance)
+}
+
+#[test]
+fn mipmapped_checkerboard_is_uniform_gray() {
+    let (mean, variance) = downscaled_checkerboard(TextureOptions::LINEAR);
+
+    assert!((mean - 127.5).abs() < 16.0, "mean {mean} isn't gray");
+    // A standard deviation of a few levels
+    assert!(variance < 25.0, "variance {variance} shows aliasing");
+}
+
+/// Makes sure the test above can see aliasing at all.
+#[test]
+fn checkerboard_without_mipmaps_aliases() {
+    let (_, variance) = downscaled_checkerboard(TextureOptions::NEAREST);
+
+    assert!(variance > 1000.0, "variance {variance}");
+}
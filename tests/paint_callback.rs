@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use egui::{LayerId, PaintCallback, Pos2, Rect};
+use egui_skia::{rasterize, EguiSkiaPaintCallback, RasterizeOptions};
+use skia_safe::{Color, Paint};
+
+/// Callbacks draw in points from the top left corner of their rect, also when no mesh was
+/// painted before them.
+#[test]
+fn callbacks_draw_in_points() {
+    let mut surface = rasterize(
+        (200, 200),
+        |ctx| {
+            ctx.layer_painter(LayerId::background()).add(PaintCallback {
+                rect: Rect::from_min_max(Pos2::new(10.0, 10.0), Pos2::new(60.0, 60.0)),
+                callback: Arc::new(EguiSkiaPaintCallback::new(|canvas| {
+                    let mut paint = Paint::default();
+                    paint.set_color(Color::RED);
+                    canvas.draw_rect(skia_safe::Rect::from_wh(50.0, 50.0), &paint);
+                })),
+            });
+        },
+        Some(RasterizeOptions {
+            pixels_per_point: 2.0,
+            ..Default::default()
+        }),
+    );
+
+    let image = surface.image_snapshot();
+    let pixels = image.peek_pixels().unwrap();
+    let is_red = |x: i32, y: i32| pixels.get_color((x, y)) == Color::RED;
+
+    // The rect spans 20..120 pixels
+    assert!(is_red(22, 22));
+    assert!(is_red(118, 118));
+    assert!(!is_red(18, 18));
+    assert!(!is_red(122, 122));
+}
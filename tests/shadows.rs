@@ -0,0 +1,70 @@
+use egui_skia::{EguiSkia, Painter, PainterOptions};
+use skia_safe::{Image, Surface};
+
+/// Maximum allowed difference per color channel within a shadow. The gaussian blur doesn't
+/// match egui's linear feathering exactly.
+const TOLERANCE: u8 = 24;
+
+/// Renders the demo windows, returning the image and the number of native shadows drawn.
+fn render(native_shadows: bool) -> (Image, usize) {
+    let mut demo = egui_demo_lib::DemoWindows::default();
+    let mut backend = EguiSkia::new();
+    backend.painter = Painter::new_with_options(PainterOptions {
+        native_shadows,
+        ..Default::default()
+    });
+
+    let mut surface = Surface::new_raster_n32_premul((1024, 756)).unwrap();
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(
+            Default::default(),
+            egui::vec2(1024.0, 756.0),
+        )),
+        pixels_per_point: Some(1.0),
+        ..Default::default()
+    };
+    // egui needs a frame to size the windows
+    for _ in 0..2 {
+        backend.run(input.clone(), |ctx| demo.ui(ctx));
+    }
+    backend.paint(surface.canvas());
+
+    (
+        surface.image_snapshot(),
+        backend.painter.stats().native_shadows,
+    )
+}
+
+#[test]
+fn demo_windows_get_native_shadows() {
+    let (_, native_shadows) = render(false);
+    assert_eq!(native_shadows, 0);
+
+    let (_, native_shadows) = render(true);
+    assert!(
+        native_shadows > 0,
+        "no shadow of the demo windows was recognized"
+    );
+}
+
+#[test]
+fn native_shadows_match_egui_shadows() {
+    let (reference, _) = render(false);
+    let (native, _) = render(true);
+
+    let reference_pixels = reference.peek_pixels().unwrap();
+    let native_pixels = native.peek_pixels().unwrap();
+    let reference_bytes: &[u8] = reference_pixels.bytes().unwrap();
+    let native_bytes: &[u8] = native_pixels.bytes().unwrap();
+
+    let max_difference = reference_bytes
+        .iter()
+        .zip(native_bytes)
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap_or_default();
+    assert!(
+        max_difference <= TOLERANCE,
+        "max channel difference {max_difference} exceeds {TOLERANCE}"
+    );
+}
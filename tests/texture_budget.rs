@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex};
+
+use egui::{Color32, LayerId, Pos2, Rect, TextureId, TextureOptions};
+use egui_skia::EguiSkia;
+use skia_safe::{Color, EncodedImageFormat, Surface};
+
+fn encoded_image() -> Vec<u8> {
+    let mut surface = Surface::new_raster_n32_premul((32, 32)).unwrap();
+    surface.canvas().clear(Color::from_rgb(200, 40, 40));
+    surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .unwrap()
+        .as_bytes()
+        .to_vec()
+}
+
+fn frame(egui_skia: &mut EguiSkia, surface: &mut Surface, images: &[TextureId]) {
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(200.0, 100.0)].into()),
+        ..Default::default()
+    };
+    egui_skia.run(input, |ctx| {
+        let painter = ctx.layer_painter(LayerId::background());
+        if images.is_empty() {
+            painter.text(
+                Pos2::new(10.0, 10.0),
+                egui::Align2::LEFT_TOP,
+                "Text",
+                egui::FontId::default(),
+                Color32::WHITE,
+            );
+        }
+        for (index, id) in images.iter().enumerate() {
+            let min = Pos2::new(10.0 + 40.0 * index as f32, 10.0);
+            painter.image(
+                *id,
+                Rect::from_min_size(min, egui::Vec2::splat(32.0)),
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        }
+    });
+    egui_skia.paint(surface.canvas());
+}
+
+#[test]
+fn least_recently_used_textures_are_evicted_to_meet_the_budget() {
+    let mut surface = Surface::new_raster_n32_premul((200, 100)).unwrap();
+    let mut egui_skia = EguiSkia::new();
+
+    // Uploads the font atlas
+    frame(&mut egui_skia, &mut surface, &[]);
+
+    let encoded = encoded_image();
+    let images: Vec<TextureId> = (0..4)
+        .map(|_| {
+            egui_skia
+                .painter
+                .load_image(&encoded, TextureOptions::NEAREST)
+                .unwrap()
+                .0
+        })
+        .collect();
+
+    let bytes_of = |egui_skia: &EguiSkia, id: TextureId| {
+        egui_skia
+            .painter
+            .texture_usage()
+            .iter()
+            .find(|texture| texture.id == id)
+            .map(|texture| texture.bytes)
+    };
+    let font_bytes = bytes_of(&egui_skia, TextureId::default()).unwrap();
+    let image_bytes = bytes_of(&egui_skia, images[0]).unwrap();
+
+    // Room for the font atlas and one image. The font atlas was used less recently than the
+    // last two images, but can't be evicted.
+    let budget = font_bytes + image_bytes;
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let callback_evicted = evicted.clone();
+    egui_skia
+        .painter
+        .set_texture_budget(budget, move |exceeded| {
+            exceeded.evict_least_recently_used();
+            assert!(exceeded.used() <= exceeded.budget());
+            callback_evicted
+                .lock()
+                .unwrap()
+                .extend_from_slice(exceeded.evicted());
+        });
+
+    frame(&mut egui_skia, &mut surface, &images[2..]);
+
+    let evicted = evicted.lock().unwrap().clone();
+    assert_eq!(evicted.len(), 3, "{evicted:?}");
+    assert!(evicted.contains(&images[0]) && evicted.contains(&images[1]));
+    assert!(!evicted.contains(&TextureId::default()));
+
+    assert!(egui_skia.painter.texture_bytes() <= budget);
+    assert!(bytes_of(&egui_skia, TextureId::default()).is_some());
+    let resident = images[2..]
+        .iter()
+        .filter(|id| bytes_of(&egui_skia, **id).is_some())
+        .count();
+    assert_eq!(resident, 1);
+}
@@ -0,0 +1,73 @@
+use egui::{Color32, ColorImage, LayerId, Pos2, Rect, TextureHandle, TextureOptions};
+use egui_skia::{EguiSkia, FrameOutput, Painter, PainterOptions};
+use skia_safe::{Color, Surface};
+
+const SIZE: i32 = 100;
+
+/// Runs a frame that covers the screen with the texture, if there is one.
+fn run(egui_skia: &mut EguiSkia, texture: &mut Option<TextureHandle>, load: bool) -> FrameOutput {
+    let input = egui::RawInput {
+        screen_rect: Some([Pos2::default(), Pos2::new(SIZE as f32, SIZE as f32)].into()),
+        ..Default::default()
+    };
+    let (_, _, frame) = egui_skia.run_detached(input, |ctx| {
+        if load {
+            let image = ColorImage::new([4, 4], Color32::RED);
+            *texture = Some(ctx.load_texture("red", image, TextureOptions::NEAREST));
+        }
+        if let Some(texture) = texture {
+            ctx.layer_painter(LayerId::background()).image(
+                texture.id(),
+                ctx.screen_rect(),
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        }
+    });
+    frame
+}
+
+fn paint(painter: &mut Painter, frame: FrameOutput) -> Color {
+    let mut surface = Surface::new_raster_n32_premul((SIZE, SIZE)).unwrap();
+    surface.canvas().clear(Color::BLACK);
+    painter.paint_frame(surface.canvas(), frame);
+    let image = surface.image_snapshot();
+    let color = image.peek_pixels().unwrap().get_color((SIZE / 2, SIZE / 2));
+    color
+}
+
+#[test]
+fn painters_sharing_a_store_upload_frames_once_and_free_textures_late() {
+    let mut egui_skia = EguiSkia::new();
+    let mut first = Painter::new();
+    let mut second =
+        Painter::new_with_texture_store(PainterOptions::default(), first.texture_store().clone());
+    assert!(first.texture_store().ptr_eq(second.texture_store()));
+
+    let mut texture = None;
+    let frame_1 = run(&mut egui_skia, &mut texture, true);
+    let id = texture.as_ref().unwrap().id();
+
+    assert_eq!(paint(&mut first, frame_1.clone()), Color::RED);
+    assert_eq!(paint(&mut second, frame_1.clone()), Color::RED);
+    // Uploading again would have counted another frame
+    let usage = first.texture_usage();
+    assert!(usage.iter().any(|texture| texture.id == id));
+    assert!(usage.iter().all(|texture| texture.last_used_frame <= 1));
+
+    // Frees the texture
+    texture = None;
+    let frame_2 = run(&mut egui_skia, &mut texture, false);
+    assert!(frame_2.textures_delta.free.contains(&id));
+    assert_eq!(paint(&mut first, frame_2.clone()), Color::BLACK);
+
+    // The second painter hasn't caught up yet and still paints the first frame
+    assert_eq!(paint(&mut second, frame_1), Color::RED);
+    assert_eq!(paint(&mut second, frame_2), Color::BLACK);
+
+    // Removed when the textures of the next frame are uploaded
+    let frame_3 = run(&mut egui_skia, &mut texture, false);
+    paint(&mut first, frame_3.clone());
+    assert!(first.texture_usage().iter().all(|texture| texture.id != id));
+    paint(&mut second, frame_3);
+}
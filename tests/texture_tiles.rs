@@ -0,0 +1,136 @@
+use egui::{Color32, LayerId, Pos2, Rect, TextureOptions};
+use egui_skia::{EguiSkia, Painter, PainterOptions, TextureWrapMode};
+use skia_safe::{Color, EncodedImageFormat, Image, Paint, Point, Shader, Surface, TileMode};
+
+const SIZE: (i32, i32) = (640, 440);
+
+/// A gradient with circles on it, so misplaced tiles show.
+fn encoded_image() -> Vec<u8> {
+    let mut surface = Surface::new_raster_n32_premul((150, 100)).unwrap();
+    let canvas = surface.canvas();
+
+    let mut paint = Paint::default();
+    paint.set_shader(Shader::linear_gradient(
+        (Point::new(0.0, 0.0), Point::new(150.0, 100.0)),
+        [Color::from_rgb(200, 40, 40), Color::from_rgb(40, 60, 220)].as_ref(),
+        None,
+        TileMode::Clamp,
+        None,
+        None,
+    ));
+    canvas.draw_paint(&paint);
+
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_color(Color::from_rgb(240, 220, 60));
+    for x in [20.0, 75.0, 130.0] {
+        canvas.draw_circle(Point::new(x, 50.0), 15.0, &paint);
+    }
+
+    surface
+        .image_snapshot()
+        .encode_to_data(EncodedImageFormat::PNG)
+        .unwrap()
+        .as_bytes()
+        .to_vec()
+}
+
+/// Draws the image magnified, clamped and repeated, with textures tiled to `max_texture_side`.
+fn render(max_texture_side: usize) -> (Image, EguiSkia) {
+    let mut egui_skia = EguiSkia::new();
+    egui_skia.painter = Painter::new_with_options(PainterOptions {
+        raster_max_texture_side: max_texture_side,
+        ..Default::default()
+    });
+
+    let encoded = encoded_image();
+    let (clamped, _) = egui_skia
+        .painter
+        .load_image(&encoded, TextureOptions::LINEAR)
+        .unwrap();
+    let (repeated, _) = egui_skia
+        .painter
+        .load_image(&encoded, TextureOptions::NEAREST)
+        .unwrap();
+    egui_skia
+        .painter
+        .set_texture_wrap_mode(repeated, TextureWrapMode::Repeat);
+
+    let input = egui::RawInput {
+        screen_rect: Some(Rect::from_min_size(
+            Pos2::ZERO,
+            egui::vec2(SIZE.0 as f32, SIZE.1 as f32),
+        )),
+        pixels_per_point: Some(1.0),
+        // Only the image is tiled, egui's font atlas keeps its usual size
+        max_texture_side: Some(8192),
+        ..Default::default()
+    };
+    egui_skia.run(input, |ctx| {
+        let painter = ctx.layer_painter(LayerId::background());
+        painter.image(
+            clamped,
+            Rect::from_min_max(Pos2::new(10.0, 10.0), Pos2::new(310.0, 210.0)),
+            Rect::from_min_max(Pos2::new(-0.1, -0.1), Pos2::new(1.1, 1.1)),
+            Color32::WHITE,
+        );
+        painter.image(
+            repeated,
+            Rect::from_min_max(Pos2::new(10.0, 220.0), Pos2::new(610.0, 420.0)),
+            Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(2.0, 1.0)),
+            Color32::WHITE,
+        );
+    });
+
+    let mut surface = Surface::new_raster_n32_premul(SIZE).unwrap();
+    egui_skia.paint(surface.canvas());
+    (surface.image_snapshot(), egui_skia)
+}
+
+#[test]
+fn tiled_textures_match_untiled_ones() {
+    let (reference, untiled) = render(8192);
+    let (tiled_image, tiled) = render(64);
+
+    // 3 by 2 tiles for each of the two images, instead of a draw call for each
+    assert!(tiled.painter.stats().draw_calls > untiled.painter.stats().draw_calls);
+
+    let reference_pixels = reference.peek_pixels().unwrap();
+    let tiled_pixels = tiled_image.peek_pixels().unwrap();
+    let reference_bytes: &[u8] = reference_pixels.bytes().unwrap();
+    let tiled_bytes: &[u8] = tiled_pixels.bytes().unwrap();
+
+    let max_difference = reference_bytes
+        .iter()
+        .zip(tiled_bytes)
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap_or_default();
+    assert!(
+        max_difference <= 2,
+        "tiled output differs by up to {max_difference}"
+    );
+}
+
+#[test]
+fn tiled_textures_only_keep_their_tiles() {
+    let (_, untiled) = render(8192);
+    let (_, tiled) = render(64);
+
+    let image_bytes = |egui_skia: &EguiSkia| -> usize {
+        egui_skia
+            .painter
+            .texture_usage()
+            .iter()
+            .filter(|texture| matches!(texture.id, egui::TextureId::User(_)))
+            .map(|texture| texture.bytes)
+            .sum()
+    };
+
+    // The borders of the tiles add a few pixels, the whole image would double the size
+    let (untiled_bytes, tiled_bytes) = (image_bytes(&untiled), image_bytes(&tiled));
+    assert!(
+        tiled_bytes < untiled_bytes * 5 / 4,
+        "tiled images use {tiled_bytes} bytes, untiled ones {untiled_bytes}"
+    );
+}